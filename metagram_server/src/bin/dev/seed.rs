@@ -124,7 +124,7 @@ async fn seed_streams(
             .map(|i| all_tags[i].clone())
             .collect();
 
-        let stream = firehose::create_stream(&mut *conn, user, &name, &tags).await?;
+        let stream = firehose::create_stream(&mut *conn, user, &name, &tags, None).await?;
         streams.push(stream);
     }
     Ok(streams)
@@ -201,10 +201,13 @@ async fn seed_hydrants(
         let hydrant = firehose::create_hydrant(
             &mut *conn,
             user,
-            &name,
-            monthly_feed_url.as_ref(),
-            active,
-            Some(tags),
+            firehose::NewHydrant {
+                name,
+                url: monthly_feed_url.to_string(),
+                active,
+                tags: Some(tags),
+                ..Default::default()
+            },
         )
         .await?;
 
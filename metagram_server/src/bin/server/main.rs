@@ -99,14 +99,27 @@ async fn main() {
         .await
         .expect("database_pool");
 
-    let worker =
-        metagram_server::queue::Worker::new(database_pool.clone(), Duration::from_secs(60));
+    let http_client = {
+        let config: metagram_server::http_client::Config = envy::prefixed("HTTP_")
+            .from_env()
+            .expect("HTTP_* settings should be valid");
+
+        metagram_server::http_client::Client::new(&config, &base_url).expect("http_client")
+    };
+
+    let worker = metagram_server::queue::Worker::new(
+        database_pool.clone(),
+        Duration::from_secs(60),
+        http_client.clone(),
+        base_url.clone(),
+    );
 
     let srv = metagram_server::Server::new(metagram_server::ServerConfig {
         auth,
         base_url,
         cookie_key,
         database_pool: database_pool.clone(),
+        http_client,
     })
     .await
     .unwrap();
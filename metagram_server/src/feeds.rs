@@ -0,0 +1,689 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use kuchikiki::traits::TendrilSink;
+use quick_xml::events::Event;
+use serde::Deserialize;
+
+// Hydrants can subscribe to RSS, Atom, or JSON Feed documents. Each format gets parsed by its own
+// library and then normalized into this common shape so the rest of Firehose doesn't have to care
+// which one it got. Sitemaps aren't feeds, but they get the same treatment so that a hydrant can
+// follow a site that only has one of those.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    pub title: Option<String>,
+    pub entries: Vec<Entry>,
+
+    // Feeds that support WebSub name the hub that pushes their updates, and the URL (topic) to
+    // subscribe to there.
+    pub hub: Option<String>,
+    pub self_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("unrecognized feed format")]
+    UnknownFormat,
+
+    #[error(transparent)]
+    Rss(#[from] rss::Error),
+
+    #[error(transparent)]
+    Atom(#[from] atom_syndication::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+}
+
+pub fn parse(content: &[u8]) -> Result<Feed, Error> {
+    if looks_like_json(content) {
+        return parse_json_feed(content);
+    }
+
+    // Both XML parsers bail out early with InvalidStartTag if the root element isn't theirs, so
+    // trying them in order is cheap.
+    match rss::Channel::read_from(content) {
+        Ok(channel) => return Ok(from_rss(channel)),
+        Err(rss::Error::InvalidStartTag) => (),
+        Err(err) => return Err(err.into()),
+    }
+
+    match atom_syndication::Feed::read_from(content) {
+        Ok(feed) => Ok(from_atom(feed)),
+        Err(atom_syndication::Error::InvalidStartTag) => Err(Error::UnknownFormat),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn looks_like_json(content: &[u8]) -> bool {
+    // Skip a UTF-8 byte order mark if there is one.
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+
+    content.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+}
+
+fn from_rss(channel: rss::Channel) -> Feed {
+    let entries = channel
+        .items
+        .into_iter()
+        .map(|item| Entry {
+            id: item.guid.map(|guid| guid.value).and_then(present),
+            title: item.title.and_then(present),
+            url: item.link.and_then(present),
+            published_at: item.pub_date.as_deref().and_then(parse_rss_date),
+        })
+        .collect();
+
+    // RSS has no links of its own for these, so they come from the Atom namespace.
+    let links = channel
+        .atom_ext
+        .as_ref()
+        .map(|ext| ext.links())
+        .unwrap_or_default();
+
+    Feed {
+        title: present(channel.title),
+        entries,
+        hub: atom_rel(links, "hub"),
+        self_url: atom_rel(links, "self"),
+    }
+}
+
+fn parse_rss_date(s: &str) -> Option<DateTime<Utc>> {
+    // RSS requires RFC 2822 dates, but enough feeds use RFC 3339 instead that it's worth a second
+    // try before giving up.
+    DateTime::parse_from_rfc2822(s.trim())
+        .or_else(|_| DateTime::parse_from_rfc3339(s.trim()))
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn from_atom(feed: atom_syndication::Feed) -> Feed {
+    let entries = feed
+        .entries()
+        .iter()
+        .map(|entry| {
+            // Prefer the entry's original publication time, but fall back to the last update
+            // since that's the only one Atom requires.
+            let published_at = entry
+                .published()
+                .or_else(|| Some(entry.updated()))
+                .and_then(atom_date);
+
+            Entry {
+                id: present(entry.id().to_string()),
+                title: present(entry.title().value.clone()),
+                url: atom_link(entry.links()),
+                published_at,
+            }
+        })
+        .collect();
+
+    Feed {
+        title: present(feed.title().value.clone()),
+        entries,
+        hub: atom_rel(feed.links(), "hub"),
+        self_url: atom_rel(feed.links(), "self"),
+    }
+}
+
+fn atom_link(links: &[atom_syndication::Link]) -> Option<String> {
+    // An entry can link to many things (comments, enclosures, ...), but the "alternate" one is the
+    // entry itself. Not every feed bothers with rel attributes, so settle for the first link if
+    // that's all there is.
+    links
+        .iter()
+        .find(|link| link.rel() == "alternate")
+        .or_else(|| links.first())
+        .map(|link| link.href().to_string())
+        .and_then(present)
+}
+
+fn atom_rel(links: &[atom_syndication::Link], rel: &str) -> Option<String> {
+    links
+        .iter()
+        .find(|link| link.rel() == rel)
+        .map(|link| link.href().to_string())
+        .and_then(present)
+}
+
+fn atom_date(dt: &DateTime<FixedOffset>) -> Option<DateTime<Utc>> {
+    // The Atom parser fills in the Unix epoch for missing dates. Treat that as "no date" instead
+    // of a real timestamp from 1970.
+    if dt.timestamp() == 0 {
+        None
+    } else {
+        Some(dt.with_timezone(&Utc))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    version: String,
+    title: Option<String>,
+    feed_url: Option<String>,
+    #[serde(default)]
+    hubs: Vec<JsonFeedHub>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedHub {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedItem {
+    // The spec says this is a string, but some publishers use numbers anyway.
+    id: Option<serde_json::Value>,
+    title: Option<String>,
+    url: Option<String>,
+    external_url: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+}
+
+fn parse_json_feed(content: &[u8]) -> Result<Feed, Error> {
+    let feed: JsonFeed = serde_json::from_slice(content)?;
+
+    if !feed.version.starts_with("https://jsonfeed.org/version/") {
+        return Err(Error::UnknownFormat);
+    }
+
+    let entries = feed
+        .items
+        .into_iter()
+        .map(|item| {
+            let id = item.id.map(|id| match id {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            });
+
+            let published_at = item
+                .date_published
+                .or(item.date_modified)
+                .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+                .map(|dt| dt.with_timezone(&Utc));
+
+            Entry {
+                id: id.and_then(present),
+                title: item.title.and_then(present),
+                url: item.url.or(item.external_url).and_then(present),
+                published_at,
+            }
+        })
+        .collect();
+
+    let hub = feed
+        .hubs
+        .into_iter()
+        .find(|hub| hub.kind.eq_ignore_ascii_case("websub"))
+        .and_then(|hub| present(hub.url));
+
+    Ok(Feed {
+        title: feed.title.and_then(present),
+        entries,
+        hub,
+        self_url: feed.feed_url.and_then(present),
+    })
+}
+
+// Read a sitemap (https://www.sitemaps.org/protocol.html) as a feed with one entry per page. Pages
+// have no titles or IDs, so they're told apart by URL alone. Sitemap indexes (lists of other
+// sitemaps) aren't supported.
+pub fn parse_sitemap(content: &[u8]) -> Result<Feed, Error> {
+    let mut reader = quick_xml::Reader::from_reader(content);
+    reader.trim_text(true);
+
+    let mut entries = Vec::new();
+
+    let mut seen_urlset = false;
+    let mut element: Vec<u8> = Vec::new();
+    let mut loc: Option<String> = None;
+    let mut lastmod: Option<String> = None;
+
+    let mut buf = Vec::new();
+    loop {
+        // Extensions like image sitemaps nest their own (prefixed) elements inside each URL, so
+        // only unprefixed names count.
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"urlset" => seen_urlset = true,
+            Event::Empty(e) if e.name().as_ref() == b"urlset" => seen_urlset = true,
+            Event::Start(e) => element = e.name().as_ref().to_vec(),
+            Event::Text(e) => match element.as_slice() {
+                b"loc" => loc = Some(e.unescape()?.into_owned()),
+                b"lastmod" => lastmod = Some(e.unescape()?.into_owned()),
+                _ => (),
+            },
+            Event::End(e) => {
+                if e.name().as_ref() == b"url" {
+                    let published_at = lastmod.take().as_deref().and_then(parse_sitemap_date);
+
+                    if let Some(url) = loc.take().and_then(present) {
+                        entries.push(Entry {
+                            id: None,
+                            title: None,
+                            url: Some(url),
+                            published_at,
+                        });
+                    }
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    if !seen_urlset {
+        return Err(Error::UnknownFormat);
+    }
+
+    Ok(Feed {
+        title: None,
+        entries,
+        hub: None,
+        self_url: None,
+    })
+}
+
+fn parse_sitemap_date(s: &str) -> Option<DateTime<Utc>> {
+    // Sitemaps use W3C datetimes, which are RFC 3339 with optional parts. Plain dates are the
+    // usual shortened form.
+    let s = s.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+// A feed that a web page advertises with a `<link rel="alternate">` tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub url: String,
+    pub title: Option<String>,
+}
+
+const FEED_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+];
+
+// Find the feeds that an HTML page links to, resolving relative URLs against the page's URL.
+// Anything that isn't HTML (or has no feed links) results in an empty list.
+pub fn discover(content: &[u8], page_url: &url::Url) -> Vec<Link> {
+    let html = String::from_utf8_lossy(content);
+    let document = kuchikiki::parse_html().one(html.as_ref());
+
+    let mut links: Vec<Link> = Vec::new();
+
+    let elements = match document.select("link[href]") {
+        Ok(elements) => elements,
+        Err(_) => return links,
+    };
+
+    for element in elements {
+        let attrs = element.attributes.borrow();
+
+        let alternate = attrs
+            .get("rel")
+            .map(|rel| {
+                rel.split_ascii_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("alternate"))
+            })
+            .unwrap_or(false);
+
+        let feed_type = attrs
+            .get("type")
+            .map(|t| FEED_TYPES.contains(&t.trim().to_ascii_lowercase().as_str()))
+            .unwrap_or(false);
+
+        if !(alternate && feed_type) {
+            continue;
+        }
+
+        let url = match attrs
+            .get("href")
+            .and_then(|href| page_url.join(href.trim()).ok())
+        {
+            Some(url) => url,
+            None => continue,
+        };
+
+        // Pages often link the same feed more than once (e.g., in the head and again for each
+        // format variant), so only keep the first of each.
+        let url = url.to_string();
+        if links.iter().any(|link| link.url == url) {
+            continue;
+        }
+
+        links.push(Link {
+            url,
+            title: attrs.get("title").map(str::to_string).and_then(present),
+        });
+    }
+
+    links
+}
+
+fn present(s: String) -> Option<String> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse_rss() {
+        let content = r#"<?xml version="1.0"?>
+            <rss version="2.0">
+              <channel>
+                <title>Lorem</title>
+                <link>https://example.com</link>
+                <description>Ipsum</description>
+                <item>
+                  <title>Dated</title>
+                  <link>https://example.com/dated</link>
+                  <guid>tag:example.com,2022:dated</guid>
+                  <pubDate>Sat, 01 Oct 2022 12:30:00 +0200</pubDate>
+                </item>
+                <item>
+                  <link>https://example.com/undated</link>
+                </item>
+              </channel>
+            </rss>
+        "#;
+
+        let feed = parse(content.as_bytes()).unwrap();
+        assert_eq!(feed.title, Some("Lorem".to_string()));
+        assert_eq!(
+            feed.entries,
+            vec![
+                Entry {
+                    id: Some("tag:example.com,2022:dated".to_string()),
+                    title: Some("Dated".to_string()),
+                    url: Some("https://example.com/dated".to_string()),
+                    published_at: Some(Utc.with_ymd_and_hms(2022, 10, 1, 10, 30, 0).unwrap()),
+                },
+                Entry {
+                    id: None,
+                    title: None,
+                    url: Some("https://example.com/undated".to_string()),
+                    published_at: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_atom() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Lorem</title>
+              <id>urn:uuid:60a76c80-d399-11d9-b93C-0003939e0af6</id>
+              <updated>2022-10-02T00:00:00Z</updated>
+              <entry>
+                <title>Published</title>
+                <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+                <link rel="edit" href="https://example.com/edit/published"/>
+                <link rel="alternate" href="https://example.com/published"/>
+                <published>2022-10-01T12:30:00+02:00</published>
+                <updated>2022-10-02T00:00:00Z</updated>
+              </entry>
+              <entry>
+                <title>Updated</title>
+                <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6b</id>
+                <link href="https://example.com/updated"/>
+                <updated>2022-10-02T00:00:00Z</updated>
+              </entry>
+            </feed>
+        "#;
+
+        let feed = parse(content.as_bytes()).unwrap();
+        assert_eq!(feed.title, Some("Lorem".to_string()));
+        assert_eq!(
+            feed.entries,
+            vec![
+                Entry {
+                    id: Some("urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a".to_string()),
+                    title: Some("Published".to_string()),
+                    url: Some("https://example.com/published".to_string()),
+                    published_at: Some(Utc.with_ymd_and_hms(2022, 10, 1, 10, 30, 0).unwrap()),
+                },
+                Entry {
+                    id: Some("urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6b".to_string()),
+                    title: Some("Updated".to_string()),
+                    url: Some("https://example.com/updated".to_string()),
+                    published_at: Some(Utc.with_ymd_and_hms(2022, 10, 2, 0, 0, 0).unwrap()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_json() {
+        let content = r#"
+            {
+              "version": "https://jsonfeed.org/version/1.1",
+              "title": "Lorem",
+              "items": [
+                {
+                  "id": "1",
+                  "title": "Published",
+                  "url": "https://example.com/published",
+                  "date_published": "2022-10-01T12:30:00+02:00"
+                },
+                {
+                  "id": 2,
+                  "external_url": "https://example.net/linked",
+                  "date_modified": "2022-10-02T00:00:00Z"
+                }
+              ]
+            }
+        "#;
+
+        let feed = parse(content.as_bytes()).unwrap();
+        assert_eq!(feed.title, Some("Lorem".to_string()));
+        assert_eq!(
+            feed.entries,
+            vec![
+                Entry {
+                    id: Some("1".to_string()),
+                    title: Some("Published".to_string()),
+                    url: Some("https://example.com/published".to_string()),
+                    published_at: Some(Utc.with_ymd_and_hms(2022, 10, 1, 10, 30, 0).unwrap()),
+                },
+                Entry {
+                    id: Some("2".to_string()),
+                    title: None,
+                    url: Some("https://example.net/linked".to_string()),
+                    published_at: Some(Utc.with_ymd_and_hms(2022, 10, 2, 0, 0, 0).unwrap()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_websub_links() {
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+              <channel>
+                <title>Lorem</title>
+                <atom:link rel="hub" href="https://hub.example.com/"/>
+                <atom:link rel="self" href="https://example.com/rss.xml"/>
+              </channel>
+            </rss>
+        "#;
+
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Lorem</title>
+              <link rel="alternate" href="https://example.com/"/>
+              <link rel="hub" href="https://hub.example.com/"/>
+              <link rel="self" href="https://example.com/atom.xml"/>
+            </feed>
+        "#;
+
+        let json = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Lorem",
+            "feed_url": "https://example.com/feed.json",
+            "hubs": [
+                {"type": "rssCloud", "url": "https://cloud.example.com/"},
+                {"type": "WebSub", "url": "https://hub.example.com/"}
+            ],
+            "items": []
+        }"#;
+
+        for (content, self_url) in [
+            (rss, "https://example.com/rss.xml"),
+            (atom, "https://example.com/atom.xml"),
+            (json, "https://example.com/feed.json"),
+        ] {
+            let feed = parse(content.as_bytes()).unwrap();
+            assert_eq!(feed.hub, Some("https://hub.example.com/".to_string()));
+            assert_eq!(feed.self_url, Some(self_url.to_string()));
+        }
+
+        let feed = parse(br#"{"version": "https://jsonfeed.org/version/1.1"}"#).unwrap();
+        assert_eq!(feed.hub, None);
+        assert_eq!(feed.self_url, None);
+    }
+
+    #[test]
+    fn parse_unknown() {
+        let res = parse(b"<html><head><title>Nope</title></head></html>");
+        assert!(matches!(res, Err(Error::UnknownFormat)));
+
+        let res = parse(br#"{"version": "1.0", "items": []}"#);
+        assert!(matches!(res, Err(Error::UnknownFormat)));
+    }
+
+    #[test]
+    fn parse_sitemaps() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                    xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+              <url>
+                <loc>https://example.com/posts/lorem?a=1&amp;b=2</loc>
+                <lastmod>2022-10-01T12:30:00+02:00</lastmod>
+                <image:image><image:loc>https://example.com/lorem.png</image:loc></image:image>
+              </url>
+              <url>
+                <loc> https://example.com/posts/ipsum </loc>
+                <lastmod>2022-09-30</lastmod>
+                <changefreq>monthly</changefreq>
+              </url>
+              <url><lastmod>2022-09-29</lastmod></url>
+            </urlset>
+        "#;
+
+        let feed = parse_sitemap(content.as_bytes()).unwrap();
+
+        assert_eq!(
+            feed,
+            Feed {
+                title: None,
+                entries: vec![
+                    Entry {
+                        id: None,
+                        title: None,
+                        url: Some("https://example.com/posts/lorem?a=1&b=2".to_string()),
+                        published_at: Some(Utc.with_ymd_and_hms(2022, 10, 1, 10, 30, 0).unwrap()),
+                    },
+                    Entry {
+                        id: None,
+                        title: None,
+                        url: Some("https://example.com/posts/ipsum".to_string()),
+                        published_at: Some(Utc.with_ymd_and_hms(2022, 9, 30, 0, 0, 0).unwrap()),
+                    },
+                ],
+                hub: None,
+                self_url: None,
+            }
+        );
+
+        let index = r#"<sitemapindex><sitemap><loc>https://example.com/a.xml</loc></sitemap></sitemapindex>"#;
+        let res = parse_sitemap(index.as_bytes());
+        assert!(matches!(res, Err(Error::UnknownFormat)));
+    }
+
+    #[test]
+    fn rss_dates() {
+        let expected = Some(Utc.with_ymd_and_hms(2022, 10, 1, 10, 30, 0).unwrap());
+
+        assert_eq!(parse_rss_date("Sat, 01 Oct 2022 10:30:00 GMT"), expected);
+        assert_eq!(parse_rss_date("Sat, 01 Oct 2022 12:30:00 +0200"), expected);
+        assert_eq!(parse_rss_date("2022-10-01T12:30:00+02:00"), expected);
+        assert_eq!(parse_rss_date("yesterday"), None);
+    }
+
+    #[test]
+    fn discover_links() {
+        let content = r#"<!doctype html>
+            <html>
+              <head>
+                <title>Lorem</title>
+                <link rel="stylesheet" href="/style.css">
+                <link rel="alternate" type="application/rss+xml" title="RSS" href="/feed.xml">
+                <link rel="Alternate" type="application/atom+xml" href="https://example.net/atom">
+                <link rel="alternate" type="application/feed+json" title=" " href="feed.json">
+                <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+                <link rel="alternate" hreflang="fr" href="/fr/">
+              </head>
+              <body><link rel="alternate" type="text/html" href="/elsewhere"></body>
+            </html>
+        "#;
+
+        let page_url: url::Url = "https://example.com/blog/".parse().unwrap();
+
+        assert_eq!(
+            discover(content.as_bytes(), &page_url),
+            vec![
+                Link {
+                    url: "https://example.com/feed.xml".to_string(),
+                    title: Some("RSS".to_string()),
+                },
+                Link {
+                    url: "https://example.net/atom".to_string(),
+                    title: None,
+                },
+                Link {
+                    url: "https://example.com/blog/feed.json".to_string(),
+                    title: None,
+                },
+            ]
+        );
+
+        assert_eq!(discover(b"not html at all", &page_url), vec![]);
+    }
+}
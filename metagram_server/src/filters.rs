@@ -5,6 +5,30 @@ pub fn pluralize(count: usize, noun: &str) -> askama::Result<String> {
     Ok(format!("{} {}", count, word))
 }
 
+// A word count, and about how long that takes to read.
+pub fn reading_time(word_count: &i32) -> askama::Result<String> {
+    let words = pluralize((*word_count).max(0) as usize, "word")?;
+    let minutes = crate::pages::reading_minutes(*word_count);
+    Ok(format!("{} · {} min read", words, minutes))
+}
+
+// Markdown that someone wrote, as HTML that's safe to show.
+pub fn render_markdown(s: &str) -> askama::Result<String> {
+    use pulldown_cmark::{html, Options, Parser};
+
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(s, options));
+
+    let html = ammonia::Builder::default()
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .clean(&unsafe_html)
+        .to_string();
+    Ok(html)
+}
+
 pub fn inflect(count: i64, singular: &str, plural: &str) -> askama::Result<String> {
     match count {
         -1 | 1 => Ok(singular.to_string()),
@@ -18,3 +42,57 @@ pub fn yes_no(b: &bool) -> askama::Result<&'static str> {
         false => Ok("no"),
     }
 }
+
+pub fn minutes(minutes: &i32) -> askama::Result<String> {
+    let minutes = *minutes as usize;
+    match minutes {
+        m if m > 0 && m % (24 * 60) == 0 => pluralize(m / (24 * 60), "day"),
+        m if m > 0 && m % 60 == 0 => pluralize(m / 60, "hour"),
+        m => pluralize(m, "minute"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_formatting() {
+        let html = render_markdown("Some *emphasis* and `code`.").unwrap();
+        assert_eq!(
+            html,
+            "<p>Some <em>emphasis</em> and <code>code</code>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn markdown_strips_scripts() {
+        let html = render_markdown("Hi <script>alert('hi')</script> there").unwrap();
+        assert!(!html.contains("<script"), "{}", html);
+        assert!(!html.contains("alert"), "{}", html);
+
+        let html = render_markdown("<img src=\"x.png\" onerror=\"alert('hi')\">").unwrap();
+        assert!(!html.contains("onerror"), "{}", html);
+
+        let html = render_markdown("<p onclick=\"alert('hi')\">Click</p>").unwrap();
+        assert!(!html.contains("onclick"), "{}", html);
+        assert!(html.contains("Click"), "{}", html);
+    }
+
+    #[test]
+    fn markdown_links() {
+        let html = render_markdown("[Example](https://example.com/)").unwrap();
+        assert_eq!(
+            html,
+            "<p><a href=\"https://example.com/\" rel=\"noopener noreferrer nofollow\">Example</a></p>\n"
+        );
+
+        let html = render_markdown("[Sneaky](javascript:alert('hi'))").unwrap();
+        assert!(!html.contains("javascript:"), "{}", html);
+        assert!(html.contains("Sneaky"), "{}", html);
+
+        let html = render_markdown("<a href=\"javascript:alert('hi')\">Sneakier</a>").unwrap();
+        assert!(!html.contains("javascript:"), "{}", html);
+        assert!(html.contains("Sneakier"), "{}", html);
+    }
+}
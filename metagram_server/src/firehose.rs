@@ -1,10 +1,17 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
+use async_trait::async_trait;
 use sqlx::{Connection, PgConnection, PgExecutor, QueryBuilder};
 use uuid::Uuid;
 
+use crate::feeds;
+use crate::http_client;
 use crate::models;
-pub use crate::models::{DropStatus, Tag};
+pub use crate::models::{DropStatus, HydrantSourceKind, Tag};
+use crate::opml;
+use crate::pages;
+use crate::urls;
+use crate::websub;
 
 type PgQueryBuilder<'a> = QueryBuilder<'a, sqlx::Postgres>;
 
@@ -12,6 +19,17 @@ type PgQueryBuilder<'a> = QueryBuilder<'a, sqlx::Postgres>;
 pub struct Drop {
     pub drop: models::Drop,
     pub tags: Vec<models::Tag>,
+    pub hydrant: Option<DropHydrant>,
+
+    // How long the drop's article is, once it's been extracted.
+    pub word_count: Option<i32>,
+}
+
+// The hydrant a drop came from, with just enough to link back to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropHydrant {
+    pub id: Uuid,
+    pub name: String,
 }
 
 // TODO: JoinAsBsRow + from_rows_one + from_rows_vec might generalize
@@ -21,6 +39,8 @@ impl Drop {
         Self {
             drop: row.drop(),
             tags: row.tag().into_iter().collect(),
+            hydrant: row.hydrant(),
+            word_count: row.article_word_count,
         }
     }
 
@@ -62,6 +82,14 @@ impl Drop {
 pub struct DropFilters {
     pub status: Option<DropStatus>,
     pub tags: Option<Vec<models::Tag>>,
+    pub hydrant_id: Option<Uuid>,
+
+    // Search terms, in the syntax of websearch_to_tsquery: words, "quoted phrases", `or`, and
+    // `-excluded`.
+    pub query: Option<String>,
+
+    // Only drops with an article that takes at most this long to read.
+    pub max_reading_minutes: Option<i32>,
 }
 
 type Timestamp = chrono::NaiveDateTime;
@@ -75,9 +103,20 @@ struct JoinDropsTagsRow {
     drop_status: DropStatus,
     drop_moved_at: Timestamp,
     drop_hydrant_id: Option<Uuid>,
+    drop_description: Option<String>,
+    drop_site_name: Option<String>,
+    drop_image_url: Option<String>,
+    drop_canonical_link: Option<String>,
+    drop_metadata_fetched_at: Option<Timestamp>,
+    drop_notes: Option<String>,
+    drop_highlights: Vec<String>,
     drop_created_at: Timestamp,
     drop_updated_at: Timestamp,
 
+    hydrant_name: Option<String>,
+
+    article_word_count: Option<i32>,
+
     tag_id: Option<Uuid>,
     tag_user_id: Option<Uuid>,
     tag_name: Option<String>,
@@ -99,9 +138,23 @@ impl JoinDropsTagsRow {
             , drops.status     as drop_status
             , drops.moved_at   as drop_moved_at
             , drops.hydrant_id as drop_hydrant_id
+
+            , drops.description         as drop_description
+            , drops.site_name           as drop_site_name
+            , drops.image_url           as drop_image_url
+            , drops.canonical_link      as drop_canonical_link
+            , drops.metadata_fetched_at as drop_metadata_fetched_at
+
+            , drops.notes      as drop_notes
+            , drops.highlights as drop_highlights
+
             , drops.created_at as drop_created_at
             , drops.updated_at as drop_updated_at
 
+            , hydrants.name as hydrant_name
+
+            , drop_articles.word_count as article_word_count
+
             , tags.id         as tag_id
             , tags.user_id    as tag_user_id
             , tags.name       as tag_name
@@ -110,6 +163,8 @@ impl JoinDropsTagsRow {
             , tags.updated_at as tag_updated_at
 
             from drops
+            left join hydrants on hydrants.id = drops.hydrant_id
+            left join drop_articles on drop_articles.drop_id = drops.id
             left join drop_tags on drop_tags.drop_id = drops.id
             left join tags on tags.id = drop_tags.tag_id
             ",
@@ -125,11 +180,25 @@ impl JoinDropsTagsRow {
             status: self.drop_status,
             moved_at: self.drop_moved_at,
             hydrant_id: self.drop_hydrant_id,
+            description: self.drop_description.clone(),
+            site_name: self.drop_site_name.clone(),
+            image_url: self.drop_image_url.clone(),
+            canonical_link: self.drop_canonical_link.clone(),
+            metadata_fetched_at: self.drop_metadata_fetched_at,
+            notes: self.drop_notes.clone(),
+            highlights: self.drop_highlights.clone(),
             created_at: self.drop_created_at,
             updated_at: self.drop_updated_at,
         }
     }
 
+    fn hydrant(&self) -> Option<DropHydrant> {
+        Some(DropHydrant {
+            id: self.drop_hydrant_id?,
+            name: self.hydrant_name.clone()?,
+        })
+    }
+
     fn tag(&self) -> Option<models::Tag> {
         self.tag_id?;
 
@@ -144,46 +213,169 @@ impl JoinDropsTagsRow {
     }
 }
 
+// A position in the order drops are listed in, which is by (moved_at, id). Written out, it's the
+// moved_at time in microseconds (as precise as Postgres gets) and the ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Cursor {
+    moved_at: Timestamp,
+    id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(drop: &models::Drop) -> Self {
+        Self {
+            moved_at: drop.moved_at,
+            id: drop.id,
+        }
+    }
+}
+
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}", self.moved_at.timestamp_micros(), self.id)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid cursor: {0:?}")]
+pub struct InvalidCursor(String);
+
+impl std::str::FromStr for Cursor {
+    type Err = InvalidCursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidCursor(s.to_string());
+
+        let (micros, id) = s.split_once('.').ok_or_else(invalid)?;
+        let micros: i64 = micros.parse().map_err(|_| invalid())?;
+
+        Ok(Self {
+            moved_at: Timestamp::from_timestamp_micros(micros).ok_or_else(invalid)?,
+            id: Uuid::parse_str(id).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl TryFrom<String> for Cursor {
+    type Error = InvalidCursor;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageCursor {
+    After(Cursor),
+    Before(Cursor),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropPage {
+    pub drops: Vec<Drop>,
+
+    // Where the neighboring pages start, if there's anything on them.
+    pub prev: Option<PageCursor>,
+    pub next: Option<PageCursor>,
+}
+
 pub async fn list_drops(
     conn: impl PgExecutor<'_>,
     user: &models::User,
     filters: DropFilters,
     limit: Option<i64>,
+) -> anyhow::Result<Vec<Drop>> {
+    select_drops(conn, user, filters, None, limit).await
+}
+
+// One page of drops, starting after (or ending before) the cursor. Without one, this is the first
+// page.
+pub async fn list_drops_page(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    filters: DropFilters,
+    cursor: Option<PageCursor>,
+    size: i64,
+) -> anyhow::Result<DropPage> {
+    // One extra drop shows whether there's another page past this one.
+    let mut drops = select_drops(conn, user, filters, cursor, Some(size + 1)).await?;
+    let more = drops.len() as i64 > size;
+
+    let (prev, next) = match cursor {
+        None | Some(PageCursor::After(_)) => {
+            if more {
+                drops.truncate(size as usize);
+            }
+
+            let prev = cursor.and(drops.first());
+            let next = drops.last().filter(|_| more);
+            (prev, next)
+        }
+        Some(PageCursor::Before(_)) => {
+            if more {
+                drops.remove(0);
+            }
+
+            let prev = drops.first().filter(|_| more);
+            let next = drops.last();
+            (prev, next)
+        }
+    };
+
+    let prev = prev.map(|drop| PageCursor::Before(Cursor::new(&drop.drop)));
+    let next = next.map(|drop| PageCursor::After(Cursor::new(&drop.drop)));
+
+    Ok(DropPage { drops, prev, next })
+}
+
+async fn select_drops(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    filters: DropFilters,
+    cursor: Option<PageCursor>,
+    limit: Option<i64>,
 ) -> anyhow::Result<Vec<Drop>> {
     let mut query = QueryBuilder::new(
         "
         with drop_ids as (
           select
-              distinct(drops.id) as id
+              drops.id as id
             , drops.moved_at as moved_at
           from drops
-          left join drop_tags on drop_tags.drop_id = drops.id
-          left join tags on tags.id = drop_tags.tag_id
         ",
     );
-    query.push(" where drops.user_id = ");
-    query.push_bind(user.id);
-
-    if let Some(status) = filters.status {
-        query.push(" and drops.status = ");
-        query.push("CAST( ");
-        query.push_bind(status.to_string());
-        query.push(" as drop_status) ");
-    }
-    if let Some(tags) = filters.tags {
-        let tag_ids: Vec<Uuid> = tags.iter().map(|t| t.id).collect();
-
-        query.push(" and tags.id = ANY(");
-        query.push_bind(tag_ids);
-        query.push(")");
-    }
-    query.push(
+    push_drop_filters(&mut query, user, filters);
+
+    // Paging backward means reading in reverse from the cursor. The page still gets put back in
+    // order below.
+    let order = match cursor {
+        Some(PageCursor::After(cursor)) => {
+            query.push(" and (drops.moved_at, drops.id) > (");
+            query.push_bind(cursor.moved_at);
+            query.push(", ");
+            query.push_bind(cursor.id);
+            query.push(")");
+            "asc"
+        }
+        Some(PageCursor::Before(cursor)) => {
+            query.push(" and (drops.moved_at, drops.id) < (");
+            query.push_bind(cursor.moved_at);
+            query.push(", ");
+            query.push_bind(cursor.id);
+            query.push(")");
+            "desc"
+        }
+        None => "asc",
+    };
+    query.push(format!(
         "
         order by
-            drops.moved_at asc
-          , drops.id asc
+            drops.moved_at {}
+          , drops.id {}
         ",
-    );
+        order, order,
+    ));
     if let Some(limit) = limit {
         query.push(" limit ");
         query.push_bind(limit);
@@ -200,8 +392,17 @@ pub async fn list_drops(
           , drops.status as drop_status
           , drops.moved_at as drop_moved_at
           , drops.hydrant_id as drop_hydrant_id
+          , drops.description as drop_description
+          , drops.site_name as drop_site_name
+          , drops.image_url as drop_image_url
+          , drops.canonical_link as drop_canonical_link
+          , drops.metadata_fetched_at as drop_metadata_fetched_at
+          , drops.notes as drop_notes
+          , drops.highlights as drop_highlights
           , drops.created_at as drop_created_at
           , drops.updated_at as drop_updated_at
+          , hydrants.name as hydrant_name
+          , drop_articles.word_count as article_word_count
           , tags.id as tag_id
           , tags.user_id as tag_user_id
           , tags.name as tag_name
@@ -210,6 +411,8 @@ pub async fn list_drops(
           , tags.updated_at as tag_updated_at
         from
           drops
+          left join hydrants on hydrants.id = drops.hydrant_id
+          left join drop_articles on drop_articles.drop_id = drops.id
           left join drop_tags on drop_tags.drop_id = drops.id
           left join tags on tags.id = drop_tags.tag_id
         where drops.id in (select id from drop_ids)
@@ -224,6 +427,137 @@ pub async fn list_drops(
     Ok(Drop::from_rows_vec(rows))
 }
 
+// Narrow a query over `drops` down to the ones matching the filters, starting with its where
+// clause.
+fn push_drop_filters(query: &mut PgQueryBuilder<'_>, user: &models::User, filters: DropFilters) {
+    query.push(" where drops.user_id = ");
+    query.push_bind(user.id);
+
+    if let Some(status) = filters.status {
+        query.push(" and drops.status = ");
+        query.push("CAST( ");
+        query.push_bind(status.to_string());
+        query.push(" as drop_status) ");
+    }
+    if let Some(tags) = filters.tags {
+        let tag_ids: Vec<Uuid> = tags.iter().map(|t| t.id).collect();
+
+        query.push(
+            "
+            and exists (
+              select 1 from drop_tags
+              where drop_tags.drop_id = drops.id
+              and drop_tags.tag_id = ANY(",
+        );
+        query.push_bind(tag_ids);
+        query.push("))");
+    }
+    if let Some(hydrant_id) = filters.hydrant_id {
+        query.push(" and drops.hydrant_id = ");
+        query.push_bind(hydrant_id);
+    }
+    if let Some(terms) = filters.query {
+        // A drop matches when its own text does, or when one of its tags does.
+        query.push(" and (drops.search @@ websearch_to_tsquery('simple', ");
+        query.push_bind(terms.clone());
+        query.push(
+            ")
+            or exists (
+              select 1 from drop_tags
+              join tags on tags.id = drop_tags.tag_id
+              where drop_tags.drop_id = drops.id
+              and to_tsvector('simple', tags.name) @@ websearch_to_tsquery('simple', ",
+        );
+        query.push_bind(terms);
+        query.push(")))");
+    }
+    if let Some(minutes) = filters.max_reading_minutes {
+        // Drops that haven't been read into an article yet could be any length, so they're left
+        // out.
+        query.push(
+            "
+            and exists (
+              select 1 from drop_articles
+              where drop_articles.drop_id = drops.id
+              and drop_articles.word_count <= ",
+        );
+        query.push_bind(minutes.saturating_mul(pages::WORDS_PER_MINUTE));
+        query.push(")");
+    }
+}
+
+// How many drops match the filters in each status, whatever status the filters ask for.
+pub async fn count_drops(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    filters: DropFilters,
+) -> sqlx::Result<DropCounts> {
+    let mut query = QueryBuilder::new(
+        "
+        select
+            count(*) filter (where drops.status = 'unread')
+          , count(*) filter (where drops.status = 'read')
+          , count(*) filter (where drops.status = 'saved')
+        from drops
+        ",
+    );
+    push_drop_filters(
+        &mut query,
+        user,
+        DropFilters {
+            status: None,
+            ..filters
+        },
+    );
+
+    let (unread, read, saved) = query.build_query_as().fetch_one(conn).await?;
+    Ok(DropCounts {
+        unread,
+        read,
+        saved,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct TagCount {
+    #[sqlx(flatten)]
+    pub tag: models::Tag,
+    pub drops: i64,
+}
+
+// The tags on drops matching the filters, and how many of those drops have each one, whatever
+// tags the filters ask for.
+pub async fn count_drop_tags(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    filters: DropFilters,
+) -> sqlx::Result<Vec<TagCount>> {
+    let mut query = QueryBuilder::new(
+        "
+        select tags.*, count(*) as drops
+        from drops
+        join drop_tags on drop_tags.drop_id = drops.id
+        join tags on tags.id = drop_tags.tag_id
+        ",
+    );
+    push_drop_filters(
+        &mut query,
+        user,
+        DropFilters {
+            tags: None,
+            ..filters
+        },
+    );
+    query.push(
+        "
+        group by tags.id
+        order by tags.name asc
+        ",
+    );
+
+    query.build_query_as().fetch_all(conn).await
+}
+
 pub async fn find_drop(
     conn: impl PgExecutor<'_>,
     user: &models::User,
@@ -262,6 +596,41 @@ async fn find_drop_record(
     .await
 }
 
+async fn load_drop_hydrant(
+    conn: impl PgExecutor<'_>,
+    drop: &models::Drop,
+) -> sqlx::Result<Option<DropHydrant>> {
+    let hydrant = sqlx::query_as!(
+        DropHydrant,
+        "
+        select id, name
+        from hydrants
+        where id = $1
+        ",
+        drop.hydrant_id,
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(hydrant)
+}
+
+async fn load_drop_word_count(
+    conn: impl PgExecutor<'_>,
+    drop: &models::Drop,
+) -> sqlx::Result<Option<i32>> {
+    sqlx::query_scalar!(
+        "
+        select word_count
+        from drop_articles
+        where drop_id = $1
+        ",
+        drop.id,
+    )
+    .fetch_optional(conn)
+    .await
+}
+
 async fn load_drop_tags(
     conn: impl PgExecutor<'_>,
     drop: &models::Drop,
@@ -281,6 +650,22 @@ async fn load_drop_tags(
     .await
 }
 
+// A drop that was just saved. It's either a new one or one that was already there with the same
+// canonical URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SavedDrop {
+    New(Drop),
+    Merged(Drop),
+}
+
+impl SavedDrop {
+    pub fn into_drop(self) -> Drop {
+        match self {
+            Self::New(drop) | Self::Merged(drop) => drop,
+        }
+    }
+}
+
 // TODO: This function signature is _awful_. Fix it.
 pub async fn create_drop(
     conn: &mut PgConnection,
@@ -291,28 +676,56 @@ pub async fn create_drop(
     tags: Option<Vec<TagSelector>>,
     now: chrono::DateTime<chrono::Utc>,
 ) -> anyhow::Result<Drop> {
+    let saved = save_drop(conn, user, title, url, hydrant_id, tags, now).await?;
+    Ok(saved.into_drop())
+}
+
+// Add a drop, unless there's already one for the same page. In that case, the tags get added to
+// that one instead, and the rest is left alone.
+pub async fn save_drop(
+    conn: &mut PgConnection,
+    user: &models::User,
+    title: Option<String>,
+    url: String,
+    hydrant_id: Option<Uuid>,
+    tags: Option<Vec<TagSelector>>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<SavedDrop> {
     let user = user.clone();
+    let canonical_url = urls::canonicalize(&url);
 
     conn.transaction(|tx| {
         Box::pin(async move {
-            let drop: models::Drop = sqlx::query_as(
+            let inserted: Option<models::Drop> = sqlx::query_as(
                 "
                 insert into drops
-                (user_id, title, url, status, moved_at, hydrant_id)
+                (user_id, title, url, canonical_url, status, moved_at, hydrant_id)
                 values
-                ($1, $2, $3, $4::drop_status, $5, $6)
+                ($1, $2, $3, $4, $5::drop_status, $6, $7)
+                on conflict (user_id, canonical_url) do nothing
                 returning *
                 ",
             )
             .bind(user.id)
             .bind(title)
             .bind(url)
+            .bind(&canonical_url)
             .bind(DropStatus::Unread)
             .bind(now.naive_utc())
             .bind(hydrant_id)
-            .fetch_one(&mut *tx)
+            .fetch_optional(&mut *tx)
             .await?;
 
+            let (drop, new) = match inserted {
+                Some(drop) => (drop, true),
+                None => {
+                    let drop = find_drop_by_canonical_url(&mut *tx, &user, &canonical_url)
+                        .await?
+                        .ok_or_else(|| anyhow::anyhow!("duplicate drop disappeared"))?;
+                    (drop, false)
+                }
+            };
+
             let selectors = tags;
             let mut tags = Vec::new();
             for sel in selectors.unwrap_or_default() {
@@ -321,20 +734,61 @@ pub async fn create_drop(
             }
 
             attach_tags(&mut *tx, &drop, &tags).await?;
-            tags.sort_by_key(|t| t.name.clone());
 
-            Ok(Drop { drop, tags })
+            let hydrant = load_drop_hydrant(&mut *tx, &drop).await?;
+            if new {
+                tags.sort_by_key(|t| t.name.clone());
+
+                Ok(SavedDrop::New(Drop {
+                    drop,
+                    tags,
+                    hydrant,
+                    word_count: None,
+                }))
+            } else {
+                let tags = load_drop_tags(&mut *tx, &drop).await?;
+                let word_count = load_drop_word_count(&mut *tx, &drop).await?;
+
+                Ok(SavedDrop::Merged(Drop {
+                    drop,
+                    tags,
+                    hydrant,
+                    word_count,
+                }))
+            }
         })
     })
     .await
 }
 
+async fn find_drop_by_canonical_url(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    canonical_url: &str,
+) -> sqlx::Result<Option<models::Drop>> {
+    sqlx::query_as(
+        "
+        select * from drops
+        where user_id = $1
+        and canonical_url = $2
+        ",
+    )
+    .bind(user.id)
+    .bind(canonical_url)
+    .fetch_optional(conn)
+    .await
+}
+
 #[derive(Default)]
 pub struct DropFields {
     pub title: Option<String>,
     pub url: Option<String>,
+    pub notes: Option<Option<String>>,
+    pub highlights: Option<Vec<String>>,
 }
 
+// Changing a drop's URL to one that another drop already has merges this drop into that one,
+// which is what gets returned.
 pub async fn update_drop(
     conn: &mut PgConnection,
     user: &models::User,
@@ -344,6 +798,9 @@ pub async fn update_drop(
 ) -> anyhow::Result<Drop> {
     let user = user.clone();
     let drop_id = drop.id;
+    let canonical_url = fields.url.as_deref().map(urls::canonicalize);
+    let notes = fields.notes.clone();
+    let highlights = fields.highlights.clone();
 
     let mut query = QueryBuilder::new("update drops set");
 
@@ -357,6 +814,18 @@ pub async fn update_drop(
     if let Some(url) = fields.url {
         assign.push(" url = ");
         assign.push_bind_unseparated(url);
+        assign.push(" canonical_url = ");
+        assign.push_bind_unseparated(canonical_url.clone());
+        do_assign = true;
+    }
+    if let Some(notes) = fields.notes {
+        assign.push(" notes = ");
+        assign.push_bind_unseparated(notes);
+        do_assign = true;
+    }
+    if let Some(highlights) = fields.highlights {
+        assign.push(" highlights = ");
+        assign.push_bind_unseparated(highlights);
         do_assign = true;
     }
 
@@ -368,6 +837,17 @@ pub async fn update_drop(
 
     conn.transaction(|tx| {
         Box::pin(async move {
+            if let Some(canonical_url) = canonical_url {
+                let existing = find_drop_by_canonical_url(&mut *tx, &user, &canonical_url)
+                    .await?
+                    .filter(|existing| existing.id != drop_id);
+
+                if let Some(existing) = existing {
+                    return merge_drop(&mut *tx, &user, drop_id, existing, tags, notes, highlights)
+                        .await;
+                }
+            }
+
             let drop = if do_assign {
                 query.build_query_as().fetch_one(&mut *tx).await?
             } else {
@@ -389,38 +869,276 @@ pub async fn update_drop(
                 load_drop_tags(&mut *tx, &drop).await?
             };
 
-            Ok(Drop { drop, tags })
+            let hydrant = load_drop_hydrant(&mut *tx, &drop).await?;
+            let word_count = load_drop_word_count(&mut *tx, &drop).await?;
+            Ok(Drop {
+                drop,
+                tags,
+                hydrant,
+                word_count,
+            })
         })
     })
     .await
 }
 
-pub async fn move_drop(
+// Move a drop's tags, notes, and highlights onto another drop for the same page, and then delete
+// it. Anything that's passed in replaces what the drop had before it's moved.
+async fn merge_drop(
     conn: &mut PgConnection,
-    drop: Drop,
-    status: DropStatus,
-    now: chrono::DateTime<chrono::Utc>,
-) -> sqlx::Result<Drop> {
-    let query = sqlx::query_as(
-        "
-        update drops
-        set status = $2::drop_status, moved_at = $3
-        where id = $1
-        returning *
-        ",
+    user: &models::User,
+    drop_id: Uuid,
+    into: models::Drop,
+    tags: Option<Vec<TagSelector>>,
+    notes: Option<Option<String>>,
+    highlights: Option<Vec<String>>,
+) -> anyhow::Result<Drop> {
+    let drop = find_drop_record(&mut *conn, user, drop_id).await?;
+
+    let tags = match tags {
+        Some(selectors) => {
+            let mut tags = Vec::new();
+            for sel in selectors {
+                let tag = find_or_create_tag(&mut *conn, user, sel).await?;
+                tags.push(tag);
+            }
+            tags
+        }
+        None => load_drop_tags(&mut *conn, &drop).await?,
+    };
+
+    attach_tags(&mut *conn, &into, &tags).await?;
+
+    let notes = notes.unwrap_or(drop.notes);
+    let highlights = highlights.unwrap_or(drop.highlights);
+
+    // Notes go after the ones already there, and highlights that are already there aren't
+    // repeated.
+    let into: models::Drop = if notes.is_some() || !highlights.is_empty() {
+        sqlx::query_as(
+            "
+            update drops set
+                notes = nullif(concat_ws(E'\\n\\n', notes, $2), '')
+              , highlights = highlights || array(
+                  select highlight from unnest($3::text[]) as highlight
+                  where highlight <> all(highlights)
+                )
+            where id = $1
+            returning *
+            ",
+        )
+        .bind(into.id)
+        .bind(notes)
+        .bind(highlights)
+        .fetch_one(&mut *conn)
+        .await?
+    } else {
+        into
+    };
+
+    sqlx::query!("delete from drop_tags where drop_id = $1", drop_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query!(
+        "delete from drops where id = $1 and user_id = $2",
+        drop_id,
+        user.id,
     )
-    .bind(drop.drop.id)
-    .bind(status)
-    .bind(now.naive_utc());
+    .execute(&mut *conn)
+    .await?;
 
-    conn.transaction(|tx| {
-        Box::pin(async move {
-            let drop = query.fetch_one(&mut *tx).await?;
-            let tags = load_drop_tags(&mut *tx, &drop).await?;
-            Ok(Drop { drop, tags })
-        })
+    let tags = load_drop_tags(&mut *conn, &into).await?;
+    let hydrant = load_drop_hydrant(&mut *conn, &into).await?;
+    let word_count = load_drop_word_count(&mut *conn, &into).await?;
+    Ok(Drop {
+        drop: into,
+        tags,
+        hydrant,
+        word_count,
     })
-    .await
+}
+
+// Fill in canonical URLs for drops that were saved before there were any, oldest first. A drop for
+// the same page as one that's already been done gets merged into that one, just like saving it
+// again would have. Returns how many drops were looked at, which is zero once they're all done.
+pub async fn canonicalize_drops(conn: &mut PgConnection, limit: i64) -> anyhow::Result<usize> {
+    let drops: Vec<models::Drop> = sqlx::query_as(
+        "
+        select * from drops
+        where canonical_url is null
+        order by created_at asc, id asc
+        limit $1
+        ",
+    )
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for drop in &drops {
+        let mut tx = conn.begin().await?;
+
+        let user = crate::auth::find_user(&mut tx, drop.user_id).await?;
+        let canonical_url = urls::canonicalize(&drop.url);
+
+        match find_drop_by_canonical_url(&mut tx, &user, &canonical_url).await? {
+            Some(existing) => {
+                merge_drop(&mut tx, &user, drop.id, existing, None, None, None).await?;
+            }
+            None => {
+                sqlx::query!(
+                    "update drops set canonical_url = $1 where id = $2",
+                    canonical_url,
+                    drop.id,
+                )
+                .execute(&mut tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+    }
+
+    Ok(drops.len())
+}
+
+// Look up a drop for a job about it. The drop might have been deleted (or merged into another one)
+// since the job was queued, and then there's nothing left to do.
+async fn find_queued_drop(
+    conn: impl PgExecutor<'_>,
+    drop_id: Uuid,
+) -> sqlx::Result<Option<models::Drop>> {
+    sqlx::query_as("select * from drops where id = $1")
+        .bind(drop_id)
+        .fetch_optional(conn)
+        .await
+}
+
+// Fill in whatever a drop is missing from what its page says about itself. Anything the drop
+// already has, like a title that someone typed in, stays the way it is.
+pub async fn fetch_drop_metadata(
+    conn: &mut PgConnection,
+    client: &http_client::Client,
+    drop_id: Uuid,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<()> {
+    let drop = match find_queued_drop(&mut *conn, drop_id).await? {
+        Some(drop) => drop,
+        None => return Ok(()),
+    };
+
+    let page = pages::fetch(client, &drop.url).await?;
+    let metadata = pages::metadata(&page);
+
+    sqlx::query!(
+        "
+        update drops set
+            title = coalesce(title, $2)
+          , description = coalesce(description, $3)
+          , site_name = coalesce(site_name, $4)
+          , image_url = coalesce(image_url, $5)
+          , canonical_link = coalesce(canonical_link, $6)
+          , metadata_fetched_at = $7
+        where id = $1
+        ",
+        drop.id,
+        metadata.title,
+        metadata.description,
+        metadata.site_name,
+        metadata.image_url,
+        metadata.canonical_url,
+        now.naive_utc(),
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Save the main text of a drop's page, so it can still be read if the page goes away. Fetching it
+// again replaces what was there.
+pub async fn extract_drop_article(
+    conn: &mut PgConnection,
+    client: &http_client::Client,
+    drop_id: Uuid,
+) -> anyhow::Result<()> {
+    let drop = match find_queued_drop(&mut *conn, drop_id).await? {
+        Some(drop) => drop,
+        None => return Ok(()),
+    };
+
+    let page = pages::fetch(client, &drop.url).await?;
+    let article = pages::article(&page)
+        .ok_or_else(|| anyhow::anyhow!("no article text found at {}", page.url))?;
+
+    sqlx::query!(
+        "
+        insert into drop_articles (drop_id, html, word_count)
+        values ($1, $2, $3)
+        on conflict (drop_id) do update set
+            html = excluded.html
+          , word_count = excluded.word_count
+        ",
+        drop.id,
+        article.html,
+        article.word_count,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_drop_article(
+    conn: impl PgExecutor<'_>,
+    drop: &models::Drop,
+) -> sqlx::Result<Option<models::DropArticle>> {
+    sqlx::query_as!(
+        models::DropArticle,
+        "
+        select * from drop_articles
+        where drop_id = $1
+        ",
+        drop.id,
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+pub async fn move_drop(
+    conn: &mut PgConnection,
+    drop: Drop,
+    status: DropStatus,
+    now: chrono::DateTime<chrono::Utc>,
+) -> sqlx::Result<Drop> {
+    let query = sqlx::query_as(
+        "
+        update drops
+        set status = $2::drop_status, moved_at = $3
+        where id = $1
+        returning *
+        ",
+    )
+    .bind(drop.drop.id)
+    .bind(status)
+    .bind(now.naive_utc());
+
+    // Moving a drop doesn't change where it came from, or how long it is.
+    let hydrant = drop.hydrant;
+    let word_count = drop.word_count;
+
+    conn.transaction(|tx| {
+        Box::pin(async move {
+            let drop = query.fetch_one(&mut *tx).await?;
+            let tags = load_drop_tags(&mut *tx, &drop).await?;
+            Ok(Drop {
+                drop,
+                tags,
+                hydrant,
+                word_count,
+            })
+        })
+    })
+    .await
 }
 
 pub async fn list_tags(
@@ -624,6 +1342,7 @@ impl CustomStream {
     pub fn filters(&self) -> DropFilters {
         DropFilters {
             tags: Some(self.tags.to_vec()),
+            max_reading_minutes: self.stream.max_reading_minutes,
             ..Default::default()
         }
     }
@@ -673,6 +1392,7 @@ struct JoinStreamsTagsRow {
     stream_user_id: Uuid,
     stream_name: String,
     stream_tag_ids: Vec<Uuid>,
+    stream_max_reading_minutes: Option<i32>,
     stream_created_at: Timestamp,
     stream_updated_at: Timestamp,
 
@@ -694,6 +1414,7 @@ impl JoinStreamsTagsRow {
             , streams.user_id    as stream_user_id
             , streams.name       as stream_name
             , streams.tag_ids    as stream_tag_ids
+            , streams.max_reading_minutes as stream_max_reading_minutes
             , streams.created_at as stream_created_at
             , streams.updated_at as stream_updated_at
 
@@ -716,6 +1437,7 @@ impl JoinStreamsTagsRow {
             user_id: self.stream_user_id,
             name: self.stream_name.clone(),
             tag_ids: self.stream_tag_ids.clone(),
+            max_reading_minutes: self.stream_max_reading_minutes,
             created_at: self.stream_created_at,
             updated_at: self.stream_updated_at,
         }
@@ -859,6 +1581,7 @@ pub async fn create_stream(
     user: &models::User,
     name: &str,
     tags: &[models::Tag],
+    max_reading_minutes: Option<i32>,
 ) -> Result<CustomStream, Error> {
     if tags.is_empty() {
         return Err(StreamError::NoTags)?;
@@ -871,14 +1594,15 @@ pub async fn create_stream(
         models::Stream,
         "
         insert into streams
-        (user_id, name, tag_ids)
+        (user_id, name, tag_ids, max_reading_minutes)
         values
-        ($1, $2, $3)
+        ($1, $2, $3, $4)
         returning *
         ",
         user.id,
         name,
         &tag_ids,
+        max_reading_minutes,
     );
 
     conn.transaction(|tx| {
@@ -895,6 +1619,7 @@ pub async fn create_stream(
 pub struct StreamFields {
     pub name: Option<String>,
     pub tag_ids: Option<Vec<Uuid>>,
+    pub max_reading_minutes: Option<Option<i32>>,
 }
 
 pub async fn update_stream(
@@ -920,6 +1645,11 @@ pub async fn update_stream(
         assign.push_bind_unseparated(tag_ids);
         do_assign = true;
     }
+    if let Some(max_reading_minutes) = fields.max_reading_minutes {
+        assign.push(" max_reading_minutes = ");
+        assign.push_bind_unseparated(max_reading_minutes);
+        do_assign = true;
+    }
 
     query.push(" where id = ");
     query.push_bind(stream.id);
@@ -943,43 +1673,219 @@ pub async fn update_stream(
 }
 
 struct Story {
+    key: String,
     title: Option<String>,
     url: String,
+    published_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// A rule for which stories a hydrant should turn into drops. Rules are stored as text, one per
+// line in the form:
+//
+// - `domain:example.com` matches links to that domain and its subdomains.
+// - `/pattern/` is a regular expression matched against the title and URL.
+// - Anything else is a keyword, matched case-insensitively against the title and URL.
+#[derive(Debug, Clone)]
+pub enum FilterRule {
+    Keyword(String),
+    Regex(regex::Regex),
+    Domain(String),
+}
+
+impl FilterRule {
+    pub fn parse(rule: &str) -> Result<Self, regex::Error> {
+        let rule = rule.trim();
+
+        if let Some(domain) = rule.strip_prefix("domain:") {
+            let domain = domain.trim().trim_matches('.').to_lowercase();
+            return Ok(Self::Domain(domain));
+        }
+
+        if rule.len() >= 2 && rule.starts_with('/') && rule.ends_with('/') {
+            let pattern = &rule[1..rule.len() - 1];
+            return Ok(Self::Regex(regex::Regex::new(pattern)?));
+        }
+
+        Ok(Self::Keyword(rule.to_lowercase()))
+    }
+
+    fn matches(&self, story: &Story) -> bool {
+        let title = story.title.as_deref().unwrap_or_default();
+
+        match self {
+            Self::Keyword(keyword) => {
+                title.to_lowercase().contains(keyword) || story.url.to_lowercase().contains(keyword)
+            }
+            Self::Regex(re) => re.is_match(title) || re.is_match(&story.url),
+            Self::Domain(domain) => {
+                let host = url::Url::parse(&story.url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_lowercase));
+
+                match host {
+                    Some(host) => host == *domain || host.ends_with(&format!(".{}", domain)),
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+struct StoryFilter {
+    include: Vec<FilterRule>,
+    exclude: Vec<FilterRule>,
 }
 
+impl StoryFilter {
+    fn new(hydrant: &models::Hydrant) -> Result<Self, regex::Error> {
+        let parse = |rules: &[String]| -> Result<Vec<FilterRule>, regex::Error> {
+            rules.iter().map(|rule| FilterRule::parse(rule)).collect()
+        };
+
+        Ok(Self {
+            include: parse(&hydrant.include_rules)?,
+            exclude: parse(&hydrant.exclude_rules)?,
+        })
+    }
+
+    // Without include rules, everything is included. Exclude rules win over include rules.
+    fn accepts(&self, story: &Story) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|r| r.matches(story));
+        let excluded = self.exclude.iter().any(|r| r.matches(story));
+
+        included && !excluded
+    }
+}
+
+// Feed items are identified by their GUID (RSS) or ID (Atom, JSON Feed) when they have one, and by
+// their link otherwise. Links are normalized first so trivial differences like host casing don't
+// make an item look new.
+fn item_key(entry: &feeds::Entry) -> Option<String> {
+    if let Some(id) = &entry.id {
+        return Some(id.clone());
+    }
+
+    let url = entry.url.as_ref()?;
+    match url::Url::parse(url) {
+        Ok(url) => Some(url.to_string()),
+        Err(_) => Some(url.clone()),
+    }
+}
+
+// The new stories in a feed, newest first. Stories without a date keep their place in the feed
+// after the dated ones, since most feeds list their newest items first anyway.
 fn extract_stories(
-    channel: rss::Channel,
+    feed: feeds::Feed,
     now: chrono::DateTime<chrono::Utc>,
     last_fetched: Option<Timestamp>,
+    mut seen: HashSet<String>,
+    tracked: bool,
 ) -> Vec<Story> {
-    channel
-        .items
+    let mut stories: Vec<Story> = feed
+        .entries
         .into_iter()
-        .filter_map(|item| {
-            let title = item.title;
-
-            // The `link` field is optional in RSS, but Firehose doesn't make sense without one.
-            let url = item.link?;
-            // All dates are optional, so assume that anything without a publish date is new. The
-            // URL itself is our last chance to de-dupe, and if that doesn't catch it, maybe it's
-            // truly new content.  ¯\_(ツ)_/¯
-            let published_at = item
-                .pub_date
-                .as_ref()
-                .and_then(|s| chrono::DateTime::parse_from_rfc2822(s).ok())
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or(now);
-
-            if let Some(fetched_at) = last_fetched {
-                if published_at.naive_utc() < fetched_at {
-                    // We've (probably) already imported this item.
-                    return None;
+        .filter_map(|entry| {
+            // `insert` is false if we've already imported this item, whatever its date says
+            // now. That also catches feeds that repeat an item.
+            let key = item_key(&entry)?;
+            if !seen.insert(key.clone()) {
+                return None;
+            }
+
+            let title = entry.title;
+
+            // Links are optional in every feed format, but Firehose doesn't make sense without
+            // one.
+            let url = entry.url?;
+
+            // Hydrants fetched before item tracking existed have nothing in `seen` yet, so fall
+            // back to comparing dates for that first fetch. All dates are optional, so assume
+            // that anything without a publish date is new.
+            if !tracked {
+                let published_at = entry.published_at.unwrap_or(now);
+
+                if let Some(fetched_at) = last_fetched {
+                    if published_at.naive_utc() < fetched_at {
+                        // We've (probably) already imported this item.
+                        return None;
+                    }
                 }
             }
 
-            Some(Story { title, url })
+            Some(Story {
+                key,
+                title,
+                url,
+                published_at: entry.published_at,
+            })
         })
-        .collect()
+        .collect();
+
+    stories.sort_by_key(|story| std::cmp::Reverse(story.published_at));
+    stories
+}
+
+// Limits on how many stories a hydrant takes in at once, so that subscribing to a busy feed (or
+// the sitemap of a big site) doesn't bury everything else in the unread stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IngestionPolicy {
+    // The first fetch only adds this many of the newest stories. The rest are skipped for good.
+    pub first_fetch_limit: Option<i32>,
+
+    // Later fetches add at most this many of the newest stories. The rest wait for the next fetch,
+    // as long as the feed still has them by then.
+    pub max_drops_per_fetch: Option<i32>,
+
+    // Stories from the first fetch are old news, so they can skip the unread stream entirely.
+    pub backfill_read: bool,
+}
+
+impl IngestionPolicy {
+    // New hydrants start with a small backfill unless someone asks for more.
+    pub fn for_new_hydrant() -> Self {
+        Self {
+            first_fetch_limit: Some(models::Hydrant::DEFAULT_FIRST_FETCH_LIMIT),
+            ..Default::default()
+        }
+    }
+
+    fn new(hydrant: &models::Hydrant) -> Self {
+        Self {
+            first_fetch_limit: hydrant.first_fetch_limit,
+            max_drops_per_fetch: hydrant.max_drops_per_fetch,
+            backfill_read: hydrant.backfill_read,
+        }
+    }
+
+    // Split the stories (newest first) into the ones to add now and the ones to leave for later.
+    // A backfill has no later, so its leftovers are dropped instead.
+    fn limit(&self, mut stories: Vec<Story>, backfill: bool) -> (Vec<Story>, Vec<Story>) {
+        let limit = if backfill {
+            self.first_fetch_limit.or(self.max_drops_per_fetch)
+        } else {
+            self.max_drops_per_fetch
+        };
+
+        let limit = match limit {
+            Some(limit) => usize::try_from(limit).unwrap_or_default(),
+            None => return (stories, Vec::new()),
+        };
+
+        let rest = stories.split_off(limit.min(stories.len()));
+        if backfill {
+            (stories, Vec::new())
+        } else {
+            (stories, rest)
+        }
+    }
+
+    fn status(&self, backfill: bool) -> DropStatus {
+        if backfill && self.backfill_read {
+            DropStatus::Read
+        } else {
+            DropStatus::Unread
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -988,329 +1894,1488 @@ pub struct Hydrant {
     pub tags: Vec<models::Tag>,
 }
 
+// Stop trying to fetch a hydrant after this many failures in a row. With exponential backoff,
+// that's spread over anywhere from most of a day (for the shortest intervals) to a couple of months
+// (for weekly hydrants).
+pub const MAX_FETCH_FAILURES: i32 = 10;
+
+// A feed that answers 404 or 410 this many times in a row isn't coming back, so there's no point
+// waiting for the usual failure limit.
+pub const MAX_GONE_FETCHES: i64 = 3;
+
+// Cap the backoff so that a hydrant that recovers doesn't sit idle for weeks. Hydrants that are
+// usually fetched less often than this just wait their usual interval instead.
+const MAX_FETCH_BACKOFF_MINUTES: i64 = 24 * 60;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum FeedError {
+    #[error("could not fetch the feed: {0}")]
+    Fetch(#[from] http_client::Error),
+
+    #[error("not a feed: {0}")]
+    Parse(#[from] feeds::Error),
+
+    #[error("the page links to more than one feed")]
+    Ambiguous(Vec<feeds::Link>),
+}
+
+impl From<reqwest::Error> for FeedError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Fetch(err.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundFeed {
+    pub url: String,
+    pub feed: feeds::Feed,
+}
+
+// Fetch and parse a feed without saving anything, to check a URL before it becomes a hydrant. If
+// the URL is a web page that links to exactly one feed, that feed is used instead. Either one is
+// replaced by where it permanently redirects to.
+pub async fn preview_feed(client: &http_client::Client, url: &str) -> Result<FoundFeed, FeedError> {
+    let response = client.get(url, Default::default()).await?;
+    let url = response.moved_to.unwrap_or_else(|| url.to_string());
+    let response = response.inner.error_for_status()?;
+    let page_url = response.url().clone();
+    let content = client.bytes(response).await?;
+
+    match feeds::parse(&content) {
+        Ok(feed) => Ok(FoundFeed { url, feed }),
+        Err(feeds::Error::UnknownFormat) => {
+            let url = discover_feed(&content, &page_url)?;
+
+            let response = client.get(&url, Default::default()).await?;
+            let url = response.moved_to.unwrap_or(url);
+            let response = response.inner.error_for_status()?;
+            let content = client.bytes(response).await?;
+            let feed = feeds::parse(&content)?;
+
+            Ok(FoundFeed { url, feed })
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Pick the feed that a web page links to, as long as there's no question which one it is.
+fn discover_feed(content: &[u8], page_url: &url::Url) -> Result<String, FeedError> {
+    let mut links = feeds::discover(content, page_url);
+
+    match links.len() {
+        0 => Err(feeds::Error::UnknownFormat.into()),
+        1 => Ok(links.remove(0).url),
+        _ => Err(FeedError::Ambiguous(links)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HydrantHealth {
+    Pending,
+    Healthy,
+    Failing,
+    Dead,
+    Gone,
+    Paused,
+}
+
+impl HydrantHealth {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Healthy => "Healthy",
+            Self::Failing => "Failing",
+            Self::Dead => "Dead",
+            Self::Gone => "Gone",
+            Self::Paused => "Paused",
+        }
+    }
+
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Pending | Self::Paused => Tag::DEFAULT_COLOR,
+            Self::Healthy => "#22C55E",
+            Self::Failing => "#EAB308",
+            Self::Dead | Self::Gone => "#EF4444",
+        }
+    }
+}
+
+// What happened during a single fetch, for the hydrant's history. Anything the fetch didn't get
+// far enough to find out stays empty.
+#[derive(Debug, Default)]
+struct FetchLog {
+    skipped: bool,
+    status: Option<i32>,
+    bytes: Option<i64>,
+    item_count: Option<i32>,
+    drop_count: Option<i32>,
+    filtered_count: Option<i32>,
+}
+
 impl Hydrant {
+    pub fn health(&self) -> HydrantHealth {
+        let hydrant = &self.hydrant;
+
+        if !hydrant.active {
+            match hydrant.deactivated_for {
+                Some(models::HydrantEventKind::Gone) => HydrantHealth::Gone,
+                Some(_) => HydrantHealth::Dead,
+                None => HydrantHealth::Paused,
+            }
+        } else if hydrant.failure_count > 0 {
+            HydrantHealth::Failing
+        } else if hydrant.fetched_at.is_none() {
+            HydrantHealth::Pending
+        } else {
+            HydrantHealth::Healthy
+        }
+    }
+
     pub async fn fetch(
         conn: &mut PgConnection,
-        client: &reqwest::Client,
+        client: &http_client::Client,
+        id: Uuid,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<()> {
+        // Anything the fetch wrote is rolled back on error, so the failure gets recorded
+        // separately.
+        let mut log = FetchLog::default();
+        let res = Self::fetch_inner(&mut *conn, client, id, now, &mut log).await;
+
+        let error = res.as_ref().err().map(|err| format!("{:#}", err));
+        if let Some(error) = &error {
+            record_fetch_failure(&mut *conn, id, now, log.status, error.clone()).await?;
+        }
+
+        if !log.skipped {
+            record_fetch(&mut *conn, id, now, log, error).await?;
+        }
+
+        res
+    }
+
+    async fn fetch_inner(
+        conn: &mut PgConnection,
+        client: &http_client::Client,
         id: Uuid,
         now: chrono::DateTime<chrono::Utc>,
+        log: &mut FetchLog,
     ) -> anyhow::Result<()> {
+        // Feeds can be slow to respond, so read what's needed for the request without locking
+        // anything and only take the lock once the response is in hand.
+        let hydrant: models::Hydrant = sqlx::query_as(
+            "
+            select *
+            from hydrants
+            where id = $1
+            ",
+        )
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        // Ignore inactive hydrants.
+        if !hydrant.active {
+            log.skipped = true;
+            return Ok(());
+        }
+
+        let download = download(client, &hydrant, log).await?;
+
         let mut tx = conn.begin().await?;
 
-        // Take a lock on the row to prevent parallel fetches.
+        // Take a lock on the row to prevent parallel fetches from saving the same stories.
         //
         // TODO: Could this be a `for no key update`?
-        let hydrant = sqlx::query_as!(
-            models::Hydrant,
+        let hydrant: models::Hydrant = sqlx::query_as(
             "
             select *
             from hydrants
             where id = $1
             for update
             ",
-            id,
         )
+        .bind(id)
         .fetch_one(&mut tx)
         .await?;
 
-        // Ignore inactive hydrants.
-        if !hydrant.active {
+        // The hydrant might have been turned off or pointed somewhere else while the request was
+        // in flight. Either way, the response isn't wanted anymore.
+        if !hydrant.active || hydrant.url != download.requested_url {
+            log.skipped = true;
             return Ok(());
         }
 
-        let content = client
-            .request(http::Method::GET, &hydrant.url)
-            .send()
-            .await?
-            .bytes()
-            .await?;
-        let channel = rss::Channel::read_from(&content[..])?;
+        // Permanent redirects and feeds found on web pages both change where the hydrant points.
+        // Leave a note each time, so the new URL doesn't come as a surprise.
+        let mut url = hydrant.url.clone();
+        if let Some(moved_to) = download.moved_to {
+            let kind = models::HydrantEventKind::Moved;
+            record_hydrant_event(&mut tx, hydrant.id, kind, Some(&url), Some(&moved_to), now)
+                .await?;
+            url = moved_to;
+        }
 
-        let user = crate::auth::find_user(&mut tx, hydrant.user_id).await?;
+        let (etag, last_modified, feed) = match download.feed {
+            Some(found) => {
+                if found.url != url {
+                    let kind = models::HydrantEventKind::Discovered;
+                    let (old_url, new_url) = (Some(url.as_str()), Some(found.url.as_str()));
+                    record_hydrant_event(&mut tx, hydrant.id, kind, old_url, new_url, now).await?;
+                    url = found.url;
+                }
 
-        let stories = extract_stories(channel, now, hydrant.fetched_at);
+                (found.etag, found.last_modified, found.feed)
+            }
+            None => {
+                // Nothing has changed since the last fetch, but that still counts as checking.
+                sqlx::query!(
+                    "
+                    update hydrants
+                    set fetched_at = $1
+                      , url = $2
+                      , failure_count = 0
+                      , last_error = null
+                      , next_attempt_at = $3
+                    where id = $4
+                    ",
+                    now.naive_utc(),
+                    url,
+                    next_attempt_at(&hydrant, now).naive_utc(),
+                    hydrant.id,
+                )
+                .execute(&mut tx)
+                .await?;
+
+                tx.commit().await?;
+                log.drop_count = Some(0);
+                log.filtered_count = Some(0);
+                return Ok(());
+            }
+        };
 
-        let tag_selectors: Vec<TagSelector> = hydrant
-            .tag_ids
-            .iter()
-            .cloned()
-            .map(|id| TagSelector::Find { id })
-            .collect();
+        // The feed might have started (or stopped) offering pushes since the last fetch.
+        update_subscription(&mut tx, &hydrant, &feed, &url).await?;
 
-        for story in stories {
-            create_drop(
-                &mut tx,
-                &user,
-                story.title,
-                story.url,
-                Some(hydrant.id),
-                Some(tag_selectors.clone()),
-                now,
-            )
-            .await?;
-        }
+        let added = add_stories(&mut tx, &hydrant, feed, now).await?;
+
+        // Stories left for later are still waiting in this version of the feed, so the next fetch
+        // can't be allowed to hear that nothing has changed.
+        let (etag, last_modified) = if added.deferred_count > 0 {
+            (None, None)
+        } else {
+            (etag, last_modified)
+        };
 
         sqlx::query!(
-            "update hydrants set fetched_at = $1 where id = $2",
+            "
+            update hydrants
+            set fetched_at = $1
+              , url = $2
+              , etag = $3
+              , last_modified = $4
+              , failure_count = 0
+              , last_error = null
+              , next_attempt_at = $5
+            where id = $6
+            ",
             now.naive_utc(),
+            url,
+            etag,
+            last_modified,
+            next_attempt_at(&hydrant, now).naive_utc(),
             hydrant.id,
         )
         .execute(&mut tx)
         .await?;
 
         tx.commit().await?;
+        log.drop_count = Some(added.drop_count);
+        log.filtered_count = Some(added.filtered_count);
         Ok(())
     }
 }
 
-impl Hydrant {
-    fn from_row(row: JoinHydrantsTagsRow) -> Self {
-        Self {
-            hydrant: row.hydrant(),
-            tags: row.tag().into_iter().collect(),
-        }
-    }
+// The result of requesting a hydrant's feed. There's no feed if the server said it hadn't changed.
+struct Download {
+    requested_url: String,
+    moved_to: Option<String>,
+    feed: Option<DownloadedFeed>,
+}
 
-    fn from_rows_one(rows: Vec<JoinHydrantsTagsRow>) -> Self {
-        let v = Self::from_rows_vec(rows);
-        v[0].clone()
-    }
-
-    fn from_rows_vec(rows: Vec<JoinHydrantsTagsRow>) -> Vec<Self> {
-        let mut hydrant_ids: Vec<Uuid> = Vec::new();
-        let mut hydrant_builds: HashMap<Uuid, Self> = HashMap::new();
+struct DownloadedFeed {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    feed: feeds::Feed,
+}
 
-        for row in rows {
-            let hydrant_id = row.hydrant_id;
+// A successful response, read all the way through, along with what the next fetch of the same URL
+// needs to know.
+struct DownloadedBody {
+    url: String,
+    page_url: url::Url,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content: Vec<u8>,
+}
 
-            match hydrant_builds.entry(hydrant_id) {
-                Entry::Occupied(mut entry) => {
-                    if let Some(tag) = row.tag() {
-                        entry.get_mut().tags.push(tag);
-                    }
-                }
-                Entry::Vacant(entry) => {
-                    entry.insert(Self::from_row(row));
-                    hydrant_ids.push(hydrant_id);
-                }
-            }
-        }
+impl DownloadedBody {
+    async fn read(
+        client: &http_client::Client,
+        url: String,
+        response: reqwest::Response,
+        log: &mut FetchLog,
+    ) -> anyhow::Result<Self> {
+        let page_url = response.url().clone();
+        let etag = header_string(&response, http::header::ETAG);
+        let last_modified = header_string(&response, http::header::LAST_MODIFIED);
+
+        let content = client.bytes(response).await?;
+        log.bytes = Some(content.len().try_into()?);
+
+        Ok(Self {
+            url,
+            page_url,
+            etag,
+            last_modified,
+            content,
+        })
+    }
 
-        let mut data: Vec<Self> = Vec::with_capacity(hydrant_ids.len());
-        for id in hydrant_ids.drain(0..) {
-            let hydrant = hydrant_builds.remove(&id).expect("id must be in map");
-            data.push(hydrant);
+    fn parsed(self, feed: feeds::Feed) -> DownloadedFeed {
+        DownloadedFeed {
+            url: self.url,
+            etag: self.etag,
+            last_modified: self.last_modified,
+            feed,
         }
-        data
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
-struct JoinHydrantsTagsRow {
-    hydrant_id: Uuid,
-    hydrant_user_id: Uuid,
-    hydrant_name: String,
-    hydrant_url: String,
-    hydrant_active: bool,
-    hydrant_tag_ids: Vec<Uuid>,
-    hydrant_fetched_at: Option<Timestamp>,
-    hydrant_created_at: Timestamp,
-    hydrant_updated_at: Timestamp,
+// Where a hydrant's stories come from. Each kind of source reads something different, but they
+// all turn it into a feeds::Feed so that everything after the download (tracking items, filtering,
+// creating drops) works the same way.
+#[async_trait]
+trait HydrantSource: Sync {
+    // Check a URL before it becomes a hydrant, without saving anything.
+    async fn preview(
+        &self,
+        client: &http_client::Client,
+        url: &str,
+    ) -> Result<FoundFeed, FeedError>;
+
+    // Turn what the hydrant's URL returned into stories. Requesting it in the first place works
+    // the same way for every source, so that's up to download.
+    async fn parse(
+        &self,
+        client: &http_client::Client,
+        body: DownloadedBody,
+        log: &mut FetchLog,
+    ) -> anyhow::Result<DownloadedFeed>;
+}
 
-    tag_id: Option<Uuid>,
-    tag_user_id: Option<Uuid>,
-    tag_name: Option<String>,
-    tag_color: Option<String>,
-    tag_created_at: Option<Timestamp>,
-    tag_updated_at: Option<Timestamp>,
+fn hydrant_source(kind: models::HydrantSourceKind) -> &'static dyn HydrantSource {
+    match kind {
+        models::HydrantSourceKind::Feed => &FeedSource,
+        models::HydrantSourceKind::Sitemap => &SitemapSource,
+    }
 }
 
-impl JoinHydrantsTagsRow {
-    fn select() -> PgQueryBuilder<'static> {
-        QueryBuilder::new(
-            "
-            select
+pub async fn preview_hydrant(
+    client: &http_client::Client,
+    source_kind: models::HydrantSourceKind,
+    url: &str,
+) -> Result<FoundFeed, FeedError> {
+    hydrant_source(source_kind).preview(client, url).await
+}
 
-              hydrants.id         as hydrant_id
-            , hydrants.user_id    as hydrant_user_id
-            , hydrants.name       as hydrant_name
-            , hydrants.url        as hydrant_url
-            , hydrants.active     as hydrant_active
-            , hydrants.tag_ids    as hydrant_tag_ids
-            , hydrants.fetched_at as hydrant_fetched_at
-            , hydrants.created_at as hydrant_created_at
-            , hydrants.updated_at as hydrant_updated_at
+// Request and parse what the hydrant points to. This doesn't touch the database, so that no
+// transaction is held open for however long the other server takes.
+async fn download(
+    client: &http_client::Client,
+    hydrant: &models::Hydrant,
+    log: &mut FetchLog,
+) -> anyhow::Result<Download> {
+    let response = client.get(&hydrant.url, cache_headers(hydrant)?).await?;
+    log.status = Some(response.inner.status().as_u16().into());
+
+    let moved_to = response.moved_to;
+    if response.inner.status() == http::StatusCode::NOT_MODIFIED {
+        return Ok(Download {
+            requested_url: hydrant.url.clone(),
+            moved_to,
+            feed: None,
+        });
+    }
 
-            , tags.id         as tag_id
-            , tags.user_id    as tag_user_id
-            , tags.name       as tag_name
-            , tags.color      as tag_color
-            , tags.created_at as tag_created_at
-            , tags.updated_at as tag_updated_at
+    let response = response.inner.error_for_status()?;
 
-            from hydrants
-            left join tags on tags.id = ANY(hydrants.tag_ids)
-            ",
-        )
+    let url = moved_to.clone().unwrap_or_else(|| hydrant.url.clone());
+    let body = DownloadedBody::read(client, url, response, log).await?;
+
+    let feed = hydrant_source(hydrant.source_kind)
+        .parse(client, body, log)
+        .await?;
+    log.item_count = Some(feed.feed.entries.len().try_into()?);
+
+    Ok(Download {
+        requested_url: hydrant.url.clone(),
+        moved_to,
+        feed: Some(feed),
+    })
+}
+
+// RSS, Atom, and JSON Feed documents, or web pages that link to one.
+struct FeedSource;
+
+#[async_trait]
+impl HydrantSource for FeedSource {
+    async fn preview(
+        &self,
+        client: &http_client::Client,
+        url: &str,
+    ) -> Result<FoundFeed, FeedError> {
+        preview_feed(client, url).await
     }
 
-    fn hydrant(&self) -> models::Hydrant {
-        models::Hydrant {
-            id: self.hydrant_id,
-            user_id: self.hydrant_user_id,
-            name: self.hydrant_name.clone(),
-            url: self.hydrant_url.clone(),
-            active: self.hydrant_active,
-            tag_ids: self.hydrant_tag_ids.clone(),
-            fetched_at: self.hydrant_fetched_at,
-            created_at: self.hydrant_created_at,
-            updated_at: self.hydrant_updated_at,
+    async fn parse(
+        &self,
+        client: &http_client::Client,
+        body: DownloadedBody,
+        log: &mut FetchLog,
+    ) -> anyhow::Result<DownloadedFeed> {
+        match feeds::parse(&body.content) {
+            Err(feeds::Error::UnknownFormat) => {
+                // This might be a web page that links to its feed. If so, switch the hydrant over
+                // to the feed itself so that later fetches go straight there.
+                let url = discover_feed(&body.content, &body.page_url)?;
+
+                let response = client.get(&url, Default::default()).await?;
+                let url = response.moved_to.unwrap_or(url);
+                let response = response.inner.error_for_status()?;
+
+                let body = DownloadedBody::read(client, url, response, log).await?;
+                let feed = feeds::parse(&body.content)?;
+                Ok(body.parsed(feed))
+            }
+            res => {
+                let feed = res?;
+                Ok(body.parsed(feed))
+            }
         }
     }
+}
 
-    fn tag(&self) -> Option<models::Tag> {
-        self.tag_id?;
+// Sitemaps, for sites that don't have a feed. Every page that shows up in one is a new story.
+struct SitemapSource;
+
+#[async_trait]
+impl HydrantSource for SitemapSource {
+    async fn preview(
+        &self,
+        client: &http_client::Client,
+        url: &str,
+    ) -> Result<FoundFeed, FeedError> {
+        let response = client.get(url, Default::default()).await?;
+        let url = response.moved_to.unwrap_or_else(|| url.to_string());
+        let response = response.inner.error_for_status()?;
+        let content = client.bytes(response).await?;
+        let feed = feeds::parse_sitemap(&content)?;
+
+        Ok(FoundFeed { url, feed })
+    }
 
-        Some(models::Tag {
-            id: self.tag_id.unwrap(),
-            user_id: self.tag_user_id.unwrap(),
-            name: self.tag_name.as_ref().unwrap().clone(),
-            color: self.tag_color.as_ref().unwrap().clone(),
-            created_at: self.tag_created_at.unwrap(),
-            updated_at: self.tag_updated_at.unwrap(),
-        })
+    async fn parse(
+        &self,
+        _client: &http_client::Client,
+        body: DownloadedBody,
+        _log: &mut FetchLog,
+    ) -> anyhow::Result<DownloadedFeed> {
+        let feed = feeds::parse_sitemap(&body.content)?;
+        Ok(body.parsed(feed))
     }
 }
 
-pub async fn list_hydrants(
-    conn: impl PgExecutor<'_>,
-    user: &models::User,
-) -> anyhow::Result<Vec<Hydrant>> {
-    let mut query = JoinHydrantsTagsRow::select();
-    query.push(" where hydrants.user_id = ");
-    query.push_bind(user.id);
-    query.push(
-        "
-        order by
-            hydrants.name asc
-          , hydrants.created_at asc
-          , tags.name asc
-        ",
-    );
+// Ask the server to skip the response body if nothing has changed since the last fetch.
+fn cache_headers(hydrant: &models::Hydrant) -> anyhow::Result<http::HeaderMap> {
+    let mut headers = http::HeaderMap::new();
+    if let Some(etag) = &hydrant.etag {
+        headers.insert(http::header::IF_NONE_MATCH, etag.parse()?);
+    }
+    if let Some(last_modified) = &hydrant.last_modified {
+        headers.insert(http::header::IF_MODIFIED_SINCE, last_modified.parse()?);
+    }
+    Ok(headers)
+}
 
-    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
-    Ok(Hydrant::from_rows_vec(rows))
+struct AddedStories {
+    drop_count: i32,
+    filtered_count: i32,
+    deferred_count: i32,
 }
 
-pub async fn stale_hydrants(
+// Turn the feed's new items into drops. Polling and pushes both end up here, so a story is only
+// added once no matter which way it arrived.
+async fn add_stories(
     conn: &mut PgConnection,
+    hydrant: &models::Hydrant,
+    feed: feeds::Feed,
     now: chrono::DateTime<chrono::Utc>,
-) -> anyhow::Result<Vec<Hydrant>> {
-    let mut query = JoinHydrantsTagsRow::select();
-    query.push("where hydrants.active = true ");
+) -> anyhow::Result<AddedStories> {
+    let user = crate::auth::find_user(&mut *conn, hydrant.user_id).await?;
 
-    query.push(" and ( ");
-    query.push(" hydrants.fetched_at is null ");
-    query.push(" or hydrants.fetched_at < ");
-    query.push_bind(now.naive_utc());
-    query.push(" ) ");
+    let keys: Vec<String> = feed.entries.iter().filter_map(item_key).collect();
 
-    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
-    Ok(Hydrant::from_rows_vec(rows))
-}
+    let tracked = sqlx::query_scalar!(
+        r#"
+        select exists(select 1 from hydrant_items where hydrant_id = $1) as "exists!"
+        "#,
+        hydrant.id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
 
-pub async fn find_hydrant(
-    conn: impl PgExecutor<'_>,
-    user: &models::User,
-    id: Uuid,
-) -> anyhow::Result<Hydrant> {
-    let user = user.clone();
+    let seen: HashSet<String> = sqlx::query_scalar!(
+        "
+        select key
+        from hydrant_items
+        where hydrant_id = $1
+        and key = any($2)
+        ",
+        hydrant.id,
+        &keys,
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect();
+
+    let filter = StoryFilter::new(hydrant)?;
+
+    let (stories, filtered): (Vec<Story>, Vec<Story>) =
+        extract_stories(feed, now, hydrant.fetched_at, seen, tracked)
+            .into_iter()
+            .partition(|story| filter.accepts(story));
+
+    // Nothing has been added before the first fetch, so everything in the feed is a backfill.
+    let backfill = hydrant.fetched_at.is_none();
+    let policy = IngestionPolicy::new(hydrant);
+    let (stories, deferred) = policy.limit(stories, backfill);
+    let status = policy.status(backfill);
+
+    let added = AddedStories {
+        drop_count: stories.len().try_into()?,
+        filtered_count: filtered.len().try_into()?,
+        deferred_count: deferred.len().try_into()?,
+    };
+
+    let tag_selectors: Vec<TagSelector> = hydrant
+        .tag_ids
+        .iter()
+        .cloned()
+        .map(|id| TagSelector::Find { id })
+        .collect();
+
+    for story in stories {
+        let saved = save_drop(
+            &mut *conn,
+            &user,
+            story.title,
+            story.url,
+            Some(hydrant.id),
+            Some(tag_selectors.clone()),
+            now,
+        )
+        .await?;
 
-    let mut query = JoinHydrantsTagsRow::select();
-    query.push(" where hydrants.user_id = ");
-    query.push_bind(user.id);
-    query.push(" and hydrants.id = ");
-    query.push_bind(id);
-    query.push(
+        // Drops that were already there stay wherever they've been put.
+        if let SavedDrop::New(drop) = saved {
+            if status != DropStatus::Unread {
+                move_drop(&mut *conn, drop, status, now).await?;
+            }
+        }
+    }
+
+    // Remember everything in the feed, not just the new stories, so that later fetches can
+    // skip them. Stories that didn't fit this time are the exception.
+    let deferred: HashSet<String> = deferred.into_iter().map(|story| story.key).collect();
+    let keys: Vec<String> = keys
+        .into_iter()
+        .filter(|key| !deferred.contains(key))
+        .collect();
+
+    sqlx::query!(
         "
-        order by
-            hydrants.name asc
-          , hydrants.created_at asc
-          , tags.name asc
+        insert into hydrant_items (hydrant_id, key)
+        select $1, unnest($2::text[])
+        on conflict do nothing
         ",
-    );
+        hydrant.id,
+        &keys,
+    )
+    .execute(&mut *conn)
+    .await?;
 
-    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
-    Ok(Hydrant::from_rows_one(rows))
+    Ok(added)
 }
 
-pub async fn create_hydrant(
+fn next_attempt_at(
+    hydrant: &models::Hydrant,
+    now: chrono::DateTime<chrono::Utc>,
+) -> chrono::DateTime<chrono::Utc> {
+    now + chrono::Duration::minutes(hydrant.fetch_interval_minutes.into())
+}
+
+// Double the wait after each consecutive failure, starting from the hydrant's usual interval.
+fn backoff_minutes(fetch_interval_minutes: i32, failure_count: i32) -> i64 {
+    let factor = 2_i64.saturating_pow(failure_count.clamp(0, 32) as u32);
+    let interval = i64::from(fetch_interval_minutes);
+
+    interval
+        .saturating_mul(factor)
+        .min(interval.max(MAX_FETCH_BACKOFF_MINUTES))
+}
+
+async fn record_fetch_failure(
     conn: &mut PgConnection,
-    user: &models::User,
-    name: &str,
-    url: &str,
-    active: bool, // TODO: Enum?
-    tags: Option<Vec<TagSelector>>,
-) -> sqlx::Result<Hydrant> {
-    let user = user.clone();
-    let name = name.to_string();
-    let url = url.to_string();
+    id: Uuid,
+    now: chrono::DateTime<chrono::Utc>,
+    status: Option<i32>,
+    error: String,
+) -> sqlx::Result<()> {
+    let hydrant: Option<models::Hydrant> = sqlx::query_as(
+        "
+        select *
+        from hydrants
+        where id = $1
+        for update
+        ",
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
 
-    conn.transaction(|tx| {
-        Box::pin(async move {
-            let selectors = tags;
-            let mut tags = Vec::new();
-            let mut tag_ids = Vec::new();
-            for sel in selectors.unwrap_or_default() {
-                let tag = find_or_create_tag(&mut *tx, &user, sel).await?;
-                tag_ids.push(tag.id);
-                tags.push(tag);
-            }
+    // The hydrant might have been deleted out from under us.
+    let hydrant = match hydrant {
+        Some(hydrant) => hydrant,
+        None => return Ok(()),
+    };
+
+    let failure_count = hydrant.failure_count + 1;
+    let backoff = backoff_minutes(hydrant.fetch_interval_minutes, failure_count);
+
+    // This fetch isn't in the history yet, so it only takes one fewer gone response there.
+    let gone = match status {
+        Some(404 | 410) => {
+            let recent_gone = sqlx::query_scalar!(
+                r#"
+                select count(*) as "count!"
+                from (
+                    select status
+                    from hydrant_fetches
+                    where hydrant_id = $1
+                    order by fetched_at desc
+                    limit $2
+                ) recent
+                where status in (404, 410)
+                "#,
+                hydrant.id,
+                MAX_GONE_FETCHES - 1,
+            )
+            .fetch_one(&mut *conn)
+            .await?;
 
-            let query = sqlx::query_as!(
-                models::Hydrant,
-                "
-                insert into hydrants
-                (user_id, name, url, active, tag_ids)
-                values
-                ($1, $2, $3, $4, $5)
-                returning *
-                ",
-                user.id,
-                name,
-                url,
-                active,
-                &tag_ids,
-            );
+            recent_gone >= MAX_GONE_FETCHES - 1
+        }
+        _ => false,
+    };
+
+    let deactivate = if !hydrant.active {
+        None
+    } else if gone {
+        Some(models::HydrantEventKind::Gone)
+    } else if failure_count >= MAX_FETCH_FAILURES {
+        Some(models::HydrantEventKind::Failing)
+    } else {
+        None
+    };
+
+    sqlx::query!(
+        "
+        update hydrants
+        set failure_count = $1
+          , last_error = $2
+          , next_attempt_at = $3
+          , active = active and $4
+          , deactivated_for = coalesce($5, deactivated_for)
+        where id = $6
+        ",
+        failure_count,
+        error,
+        (now + chrono::Duration::minutes(backoff)).naive_utc(),
+        deactivate.is_none(),
+        deactivate as Option<models::HydrantEventKind>,
+        hydrant.id,
+    )
+    .execute(&mut *conn)
+    .await?;
 
-            let hydrant = query.fetch_one(&mut *tx).await?;
+    if let Some(kind) = deactivate {
+        record_hydrant_event(&mut *conn, hydrant.id, kind, Some(&hydrant.url), None, now).await?;
+    }
 
-            tags.sort_by_key(|t| t.name.clone());
-            Ok(Hydrant { hydrant, tags })
-        })
-    })
-    .await
+    Ok(())
 }
 
-// TODO: Move *Fields to models?
-#[derive(Default)]
-pub struct HydrantFields {
-    pub name: Option<String>,
-    pub url: Option<String>,
-    pub active: Option<bool>,
-    pub tags: Option<Vec<TagSelector>>,
+async fn record_hydrant_event(
+    conn: impl PgExecutor<'_>,
+    hydrant_id: Uuid,
+    kind: models::HydrantEventKind,
+    old_url: Option<&str>,
+    new_url: Option<&str>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        insert into hydrant_events
+        (hydrant_id, kind, old_url, new_url, occurred_at)
+        values
+        ($1, $2, $3, $4, $5)
+        ",
+        hydrant_id,
+        kind as models::HydrantEventKind,
+        old_url,
+        new_url,
+        now.naive_utc(),
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
 }
 
-pub async fn update_hydrant(
-    conn: &mut PgConnection,
-    user: &models::User,
+pub async fn list_hydrant_events(
+    conn: impl PgExecutor<'_>,
     hydrant: &models::Hydrant,
-    fields: HydrantFields,
-) -> anyhow::Result<Hydrant> {
-    let user = user.clone();
-    let hydrant = hydrant.clone();
-
-    let mut query = QueryBuilder::new("update hydrants set");
+    limit: i64,
+) -> sqlx::Result<Vec<models::HydrantEvent>> {
+    sqlx::query_as!(
+        models::HydrantEvent,
+        r#"
+        select id, hydrant_id, kind as "kind: _", old_url, new_url, occurred_at, created_at
+        from hydrant_events
+        where hydrant_id = $1
+        order by occurred_at desc
+        limit $2
+        "#,
+        hydrant.id,
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+}
+
+async fn record_fetch(
+    conn: &mut PgConnection,
+    id: Uuid,
+    now: chrono::DateTime<chrono::Utc>,
+    log: FetchLog,
+    error: Option<String>,
+) -> sqlx::Result<()> {
+    // Select from hydrants so that nothing is logged if the hydrant was deleted mid-fetch.
+    sqlx::query!(
+        "
+        insert into hydrant_fetches
+        (hydrant_id, fetched_at, status, bytes, item_count, drop_count, filtered_count, error)
+        select id, $2, $3, $4, $5, $6, $7, $8
+        from hydrants
+        where id = $1
+        ",
+        id,
+        now.naive_utc(),
+        log.status,
+        log.bytes,
+        log.item_count,
+        log.drop_count,
+        log.filtered_count,
+        error,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_hydrant_fetches(
+    conn: impl PgExecutor<'_>,
+    hydrant: &models::Hydrant,
+    limit: i64,
+) -> sqlx::Result<Vec<models::HydrantFetch>> {
+    sqlx::query_as!(
+        models::HydrantFetch,
+        "
+        select *
+        from hydrant_fetches
+        where hydrant_id = $1
+        order by fetched_at desc
+        limit $2
+        ",
+        hydrant.id,
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+}
+
+pub async fn clear_hydrant_fetches(
+    conn: impl PgExecutor<'_>,
+    before: chrono::DateTime<chrono::Utc>,
+) -> sqlx::Result<u64> {
+    let res = sqlx::query!(
+        "
+        delete from hydrant_fetches
+        where fetched_at < $1
+        ",
+        before.naive_utc(),
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(res.rows_affected())
+}
+
+// Hubs are asked to renew subscriptions this long before they run out, and asked again if they
+// haven't confirmed a request after the retry delay. Polling keeps going the whole time, so a
+// lapsed subscription only means slower updates.
+const SUBSCRIPTION_RENEW_BEFORE_HOURS: i64 = 48;
+const SUBSCRIPTION_RETRY_HOURS: i64 = 24;
+
+// Hubs choose how long a subscription lasts. Anything longer than this (or negative) is refused,
+// since it can only be a mistake or someone messing with the callback.
+const MAX_LEASE_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+// Keep the hydrant's WebSub subscription in line with the hub its feed advertises. A new hub or
+// topic means a new subscription, which starts out unrequested so it gets picked up right away.
+async fn update_subscription(
+    conn: &mut PgConnection,
+    hydrant: &models::Hydrant,
+    feed: &feeds::Feed,
+    url: &str,
+) -> sqlx::Result<()> {
+    let hub_url = match &feed.hub {
+        Some(hub_url) => hub_url,
+        None => {
+            sqlx::query!(
+                "
+                delete from hydrant_subscriptions
+                where hydrant_id = $1
+                ",
+                hydrant.id,
+            )
+            .execute(&mut *conn)
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let topic_url = feed.self_url.as_deref().unwrap_or(url);
+
+    sqlx::query!(
+        "
+        insert into hydrant_subscriptions
+        (hydrant_id, hub_url, topic_url, secret)
+        values
+        ($1, $2, $3, $4)
+        on conflict (hydrant_id) do update
+        set hub_url = excluded.hub_url
+          , topic_url = excluded.topic_url
+          , requested_at = null
+          , lease_expires_at = null
+        where hydrant_subscriptions.hub_url != excluded.hub_url
+        or hydrant_subscriptions.topic_url != excluded.topic_url
+        ",
+        hydrant.id,
+        hub_url,
+        topic_url,
+        websub::secret(),
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_subscription(
+    conn: impl PgExecutor<'_>,
+    hydrant_id: Uuid,
+) -> sqlx::Result<Option<models::HydrantSubscription>> {
+    sqlx::query_as!(
+        models::HydrantSubscription,
+        "
+        select *
+        from hydrant_subscriptions
+        where hydrant_id = $1
+        ",
+        hydrant_id,
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+// Subscriptions of active hydrants that have never been requested, are about to run out, or were
+// requested a while ago without the hub ever confirming.
+pub async fn due_subscriptions(
+    conn: impl PgExecutor<'_>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> sqlx::Result<Vec<models::HydrantSubscription>> {
+    let renew_before = now + chrono::Duration::hours(SUBSCRIPTION_RENEW_BEFORE_HOURS);
+    let retry_before = now - chrono::Duration::hours(SUBSCRIPTION_RETRY_HOURS);
+
+    sqlx::query_as!(
+        models::HydrantSubscription,
+        "
+        select hydrant_subscriptions.*
+        from hydrant_subscriptions
+        join hydrants on hydrants.id = hydrant_subscriptions.hydrant_id
+        where hydrants.active
+        and (
+            hydrant_subscriptions.requested_at is null
+            or (
+                hydrant_subscriptions.requested_at <= $1
+                and (
+                    hydrant_subscriptions.lease_expires_at is null
+                    or hydrant_subscriptions.lease_expires_at <= $2
+                )
+            )
+        )
+        ",
+        retry_before.naive_utc(),
+        renew_before.naive_utc(),
+    )
+    .fetch_all(conn)
+    .await
+}
+
+// Ask the hub for pushes to the callback URL. This has to run after the subscription is committed,
+// since the hub might check back with the callback before it even responds.
+pub async fn request_subscription(
+    conn: impl PgExecutor<'_>,
+    client: &http_client::Client,
+    subscription: &models::HydrantSubscription,
+    callback: &url::Url,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<()> {
+    websub::subscribe(
+        client,
+        &subscription.hub_url,
+        &subscription.topic_url,
+        callback.as_str(),
+        &subscription.secret,
+    )
+    .await?;
+
+    sqlx::query!(
+        "
+        update hydrant_subscriptions
+        set requested_at = $1
+        where id = $2
+        ",
+        now.naive_utc(),
+        subscription.id,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// Answer the hub's check that a subscription really was requested. Only subscriptions that match
+// what was asked for (and whose hydrant still wants updates) get confirmed.
+pub async fn verify_subscription(
+    conn: &mut PgConnection,
+    hydrant_id: Uuid,
+    verification: &websub::Verification,
+    now: chrono::DateTime<chrono::Utc>,
+) -> sqlx::Result<bool> {
+    let subscription = match find_subscription(&mut *conn, hydrant_id).await? {
+        Some(subscription) if subscription.topic_url == verification.topic => subscription,
+        _ => return Ok(false),
+    };
+
+    let active = sqlx::query_scalar!(
+        "
+        select active
+        from hydrants
+        where id = $1
+        ",
+        hydrant_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let lease_expires_at = match verification.mode {
+        websub::Mode::Subscribe if active => match verification.lease_seconds {
+            Some(seconds) => match lease_expires_at(now, seconds) {
+                Some(expires_at) => Some(expires_at),
+                None => return Ok(false),
+            },
+            None => None,
+        },
+        websub::Mode::Denied => {
+            tracing::warn!({ ?hydrant_id, ?verification }, "Hub denied subscription");
+            None
+        }
+        // Unsubscribing is never requested, so someone else must be trying it.
+        _ => return Ok(false),
+    };
+
+    sqlx::query!(
+        "
+        update hydrant_subscriptions
+        set lease_expires_at = $1
+        where id = $2
+        ",
+        lease_expires_at,
+        subscription.id,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(true)
+}
+
+fn lease_expires_at(now: chrono::DateTime<chrono::Utc>, seconds: i64) -> Option<Timestamp> {
+    if !(0..=MAX_LEASE_SECONDS).contains(&seconds) {
+        return None;
+    }
+
+    now.checked_add_signed(chrono::Duration::seconds(seconds))
+        .map(|expires_at| expires_at.naive_utc())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Added(i32),
+    Ignored,
+    NotSubscribed,
+}
+
+impl Hydrant {
+    // Save the stories from content a hub pushed to the hydrant. Content without a valid signature
+    // is ignored instead of rejected, as WebSub requires, so that forgers can't tell whether they
+    // got anything right.
+    pub async fn receive(
+        conn: &mut PgConnection,
+        id: Uuid,
+        signature: Option<&str>,
+        content: &[u8],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<PushOutcome> {
+        let subscription = match find_subscription(&mut *conn, id).await? {
+            Some(subscription) => subscription,
+            None => return Ok(PushOutcome::NotSubscribed),
+        };
+
+        if !websub::verify_signature(&subscription.secret, signature, content) {
+            tracing::warn!({ hydrant_id = ?id }, "Ignoring push with a bad signature");
+            return Ok(PushOutcome::Ignored);
+        }
+
+        // Failing here would only get the same content pushed again, and it still wouldn't parse.
+        let feed = match feeds::parse(content) {
+            Ok(feed) => feed,
+            Err(err) => {
+                tracing::warn!({ hydrant_id = ?id, ?err }, "Ignoring push that isn't a feed");
+                return Ok(PushOutcome::Ignored);
+            }
+        };
+
+        let mut tx = conn.begin().await?;
+
+        let hydrant: models::Hydrant = sqlx::query_as(
+            "
+            select *
+            from hydrants
+            where id = $1
+            for update
+            ",
+        )
+        .bind(id)
+        .fetch_one(&mut tx)
+        .await?;
+
+        if !hydrant.active {
+            return Ok(PushOutcome::Ignored);
+        }
+
+        let added = add_stories(&mut tx, &hydrant, feed, now).await?;
+
+        // Same as when fetching: the stories left for later have to be there on the next fetch.
+        if added.deferred_count > 0 {
+            sqlx::query!(
+                "
+                update hydrants
+                set etag = null
+                  , last_modified = null
+                where id = $1
+                ",
+                hydrant.id,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(PushOutcome::Added(added.drop_count))
+    }
+}
+
+fn header_string(response: &reqwest::Response, name: http::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+impl Hydrant {
+    fn from_row(row: JoinHydrantsTagsRow) -> Self {
+        Self {
+            hydrant: row.hydrant(),
+            tags: row.tag().into_iter().collect(),
+        }
+    }
+
+    fn from_rows_one(rows: Vec<JoinHydrantsTagsRow>) -> Self {
+        let v = Self::from_rows_vec(rows);
+        v[0].clone()
+    }
+
+    fn from_rows_vec(rows: Vec<JoinHydrantsTagsRow>) -> Vec<Self> {
+        let mut hydrant_ids: Vec<Uuid> = Vec::new();
+        let mut hydrant_builds: HashMap<Uuid, Self> = HashMap::new();
+
+        for row in rows {
+            let hydrant_id = row.hydrant_id;
+
+            match hydrant_builds.entry(hydrant_id) {
+                Entry::Occupied(mut entry) => {
+                    if let Some(tag) = row.tag() {
+                        entry.get_mut().tags.push(tag);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(Self::from_row(row));
+                    hydrant_ids.push(hydrant_id);
+                }
+            }
+        }
+
+        let mut data: Vec<Self> = Vec::with_capacity(hydrant_ids.len());
+        for id in hydrant_ids.drain(0..) {
+            let hydrant = hydrant_builds.remove(&id).expect("id must be in map");
+            data.push(hydrant);
+        }
+        data
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+struct JoinHydrantsTagsRow {
+    hydrant_id: Uuid,
+    hydrant_user_id: Uuid,
+    hydrant_name: String,
+    hydrant_url: String,
+    hydrant_active: bool,
+    hydrant_tag_ids: Vec<Uuid>,
+    hydrant_fetched_at: Option<Timestamp>,
+    hydrant_etag: Option<String>,
+    hydrant_last_modified: Option<String>,
+    hydrant_fetch_interval_minutes: i32,
+    hydrant_failure_count: i32,
+    hydrant_last_error: Option<String>,
+    hydrant_next_attempt_at: Option<Timestamp>,
+    hydrant_include_rules: Vec<String>,
+    hydrant_exclude_rules: Vec<String>,
+    hydrant_source_kind: models::HydrantSourceKind,
+    hydrant_first_fetch_limit: Option<i32>,
+    hydrant_max_drops_per_fetch: Option<i32>,
+    hydrant_backfill_read: bool,
+    hydrant_deactivated_for: Option<models::HydrantEventKind>,
+    hydrant_created_at: Timestamp,
+    hydrant_updated_at: Timestamp,
+
+    tag_id: Option<Uuid>,
+    tag_user_id: Option<Uuid>,
+    tag_name: Option<String>,
+    tag_color: Option<String>,
+    tag_created_at: Option<Timestamp>,
+    tag_updated_at: Option<Timestamp>,
+}
+
+impl JoinHydrantsTagsRow {
+    fn select() -> PgQueryBuilder<'static> {
+        QueryBuilder::new(
+            "
+            select
+
+              hydrants.id                     as hydrant_id
+            , hydrants.user_id                as hydrant_user_id
+            , hydrants.name                   as hydrant_name
+            , hydrants.url                    as hydrant_url
+            , hydrants.active                 as hydrant_active
+            , hydrants.tag_ids                as hydrant_tag_ids
+            , hydrants.fetched_at             as hydrant_fetched_at
+            , hydrants.etag                   as hydrant_etag
+            , hydrants.last_modified          as hydrant_last_modified
+            , hydrants.fetch_interval_minutes as hydrant_fetch_interval_minutes
+            , hydrants.failure_count          as hydrant_failure_count
+            , hydrants.last_error             as hydrant_last_error
+            , hydrants.next_attempt_at        as hydrant_next_attempt_at
+            , hydrants.include_rules          as hydrant_include_rules
+            , hydrants.exclude_rules          as hydrant_exclude_rules
+            , hydrants.source_kind            as hydrant_source_kind
+            , hydrants.first_fetch_limit      as hydrant_first_fetch_limit
+            , hydrants.max_drops_per_fetch    as hydrant_max_drops_per_fetch
+            , hydrants.backfill_read          as hydrant_backfill_read
+            , hydrants.deactivated_for        as hydrant_deactivated_for
+            , hydrants.created_at             as hydrant_created_at
+            , hydrants.updated_at             as hydrant_updated_at
+
+            , tags.id         as tag_id
+            , tags.user_id    as tag_user_id
+            , tags.name       as tag_name
+            , tags.color      as tag_color
+            , tags.created_at as tag_created_at
+            , tags.updated_at as tag_updated_at
+
+            from hydrants
+            left join tags on tags.id = ANY(hydrants.tag_ids)
+            ",
+        )
+    }
+
+    fn hydrant(&self) -> models::Hydrant {
+        models::Hydrant {
+            id: self.hydrant_id,
+            user_id: self.hydrant_user_id,
+            name: self.hydrant_name.clone(),
+            url: self.hydrant_url.clone(),
+            active: self.hydrant_active,
+            tag_ids: self.hydrant_tag_ids.clone(),
+            fetched_at: self.hydrant_fetched_at,
+            etag: self.hydrant_etag.clone(),
+            last_modified: self.hydrant_last_modified.clone(),
+            fetch_interval_minutes: self.hydrant_fetch_interval_minutes,
+            failure_count: self.hydrant_failure_count,
+            last_error: self.hydrant_last_error.clone(),
+            next_attempt_at: self.hydrant_next_attempt_at,
+            include_rules: self.hydrant_include_rules.clone(),
+            exclude_rules: self.hydrant_exclude_rules.clone(),
+            source_kind: self.hydrant_source_kind,
+            first_fetch_limit: self.hydrant_first_fetch_limit,
+            max_drops_per_fetch: self.hydrant_max_drops_per_fetch,
+            backfill_read: self.hydrant_backfill_read,
+            deactivated_for: self.hydrant_deactivated_for,
+            created_at: self.hydrant_created_at,
+            updated_at: self.hydrant_updated_at,
+        }
+    }
+
+    fn tag(&self) -> Option<models::Tag> {
+        self.tag_id?;
+
+        Some(models::Tag {
+            id: self.tag_id.unwrap(),
+            user_id: self.tag_user_id.unwrap(),
+            name: self.tag_name.as_ref().unwrap().clone(),
+            color: self.tag_color.as_ref().unwrap().clone(),
+            created_at: self.tag_created_at.unwrap(),
+            updated_at: self.tag_updated_at.unwrap(),
+        })
+    }
+}
+
+pub async fn list_hydrants(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+) -> anyhow::Result<Vec<Hydrant>> {
+    let mut query = JoinHydrantsTagsRow::select();
+    query.push(" where hydrants.user_id = ");
+    query.push_bind(user.id);
+    query.push(
+        "
+        order by
+            hydrants.name asc
+          , hydrants.created_at asc
+          , tags.name asc
+        ",
+    );
+
+    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
+    Ok(Hydrant::from_rows_vec(rows))
+}
+
+pub async fn stale_hydrants(
+    conn: &mut PgConnection,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<Vec<Hydrant>> {
+    let mut query = JoinHydrantsTagsRow::select();
+    query.push("where hydrants.active = true ");
+
+    query.push(" and ( ");
+    query.push(" hydrants.next_attempt_at is null ");
+    query.push(" or hydrants.next_attempt_at <= ");
+    query.push_bind(now.naive_utc());
+    query.push(" ) ");
+
+    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
+    Ok(Hydrant::from_rows_vec(rows))
+}
+
+pub async fn find_hydrant(
+    conn: impl PgExecutor<'_>,
+    user: &models::User,
+    id: Uuid,
+) -> anyhow::Result<Hydrant> {
+    let user = user.clone();
+
+    let mut query = JoinHydrantsTagsRow::select();
+    query.push(" where hydrants.user_id = ");
+    query.push_bind(user.id);
+    query.push(" and hydrants.id = ");
+    query.push_bind(id);
+    query.push(
+        "
+        order by
+            hydrants.name asc
+          , hydrants.created_at asc
+          , tags.name asc
+        ",
+    );
+
+    let rows: Vec<JoinHydrantsTagsRow> = query.build_query_as().fetch_all(conn).await?;
+    Ok(Hydrant::from_rows_one(rows))
+}
+
+pub struct NewHydrant {
+    pub name: String,
+    pub url: String,
+    pub source_kind: models::HydrantSourceKind,
+    pub active: bool, // TODO: Enum?
+    pub fetch_interval_minutes: i32,
+    pub rules: FilterRules,
+    pub policy: IngestionPolicy,
+    pub tags: Option<Vec<TagSelector>>,
+}
+
+impl Default for NewHydrant {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            url: String::new(),
+            source_kind: Default::default(),
+            active: true,
+            fetch_interval_minutes: models::Hydrant::DEFAULT_FETCH_INTERVAL_MINUTES,
+            rules: Default::default(),
+            policy: Default::default(),
+            tags: None,
+        }
+    }
+}
+
+pub async fn create_hydrant(
+    conn: &mut PgConnection,
+    user: &models::User,
+    fields: NewHydrant,
+) -> sqlx::Result<Hydrant> {
+    let user = user.clone();
+    let NewHydrant {
+        name,
+        url,
+        source_kind,
+        active,
+        fetch_interval_minutes,
+        rules,
+        policy,
+        tags,
+    } = fields;
+
+    conn.transaction(|tx| {
+        Box::pin(async move {
+            let selectors = tags;
+            let mut tags = Vec::new();
+            let mut tag_ids = Vec::new();
+            for sel in selectors.unwrap_or_default() {
+                let tag = find_or_create_tag(&mut *tx, &user, sel).await?;
+                tag_ids.push(tag.id);
+                tags.push(tag);
+            }
+
+            let hydrant: models::Hydrant = sqlx::query_as(
+                "
+                insert into hydrants
+                (user_id, name, url, source_kind, active, fetch_interval_minutes, include_rules, exclude_rules, first_fetch_limit, max_drops_per_fetch, backfill_read, tag_ids)
+                values
+                ($1, $2, $3, $4::hydrant_source_kind, $5, $6, $7, $8, $9, $10, $11, $12)
+                returning *
+                ",
+            )
+            .bind(user.id)
+            .bind(name)
+            .bind(url)
+            .bind(source_kind)
+            .bind(active)
+            .bind(fetch_interval_minutes)
+            .bind(rules.include)
+            .bind(rules.exclude)
+            .bind(policy.first_fetch_limit)
+            .bind(policy.max_drops_per_fetch)
+            .bind(policy.backfill_read)
+            .bind(tag_ids)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            tags.sort_by_key(|t| t.name.clone());
+            Ok(Hydrant { hydrant, tags })
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterRules {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+// TODO: Move *Fields to models?
+#[derive(Default)]
+pub struct HydrantFields {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub source_kind: Option<models::HydrantSourceKind>,
+    pub active: Option<bool>,
+    pub fetch_interval_minutes: Option<i32>,
+    pub rules: Option<FilterRules>,
+    pub policy: Option<IngestionPolicy>,
+    pub tags: Option<Vec<TagSelector>>,
+}
+
+pub async fn update_hydrant(
+    conn: &mut PgConnection,
+    user: &models::User,
+    hydrant: &models::Hydrant,
+    fields: HydrantFields,
+) -> anyhow::Result<Hydrant> {
+    let user = user.clone();
+    let hydrant = hydrant.clone();
+
+    let mut query = QueryBuilder::new("update hydrants set");
 
     let mut assign = query.separated(" , ");
     let mut do_assign = false;
@@ -1319,242 +3384,2165 @@ pub async fn update_hydrant(
         assign.push_bind_unseparated(name);
         do_assign = true;
     }
-    if let Some(url) = fields.url {
-        assign.push(" url = ");
-        assign.push_bind_unseparated(url);
-        do_assign = true;
+    // Changing where or how often to fetch means the old schedule no longer applies, so fetch
+    // again on the next pass.
+    let mut reschedule = false;
+    // The cache validators belong to the old URL (and the old way of reading it), so forget them
+    // if either one changes.
+    let mut forget_validators = false;
+    if let Some(url) = fields.url {
+        forget_validators |= url != hydrant.url;
+        assign.push(" url = ");
+        assign.push_bind_unseparated(url);
+        do_assign = true;
+    }
+    if let Some(source_kind) = fields.source_kind {
+        forget_validators |= source_kind != hydrant.source_kind;
+        assign.push(" source_kind = ");
+        assign.push_bind_unseparated(source_kind);
+        do_assign = true;
+    }
+    if forget_validators {
+        assign.push(" etag = null ");
+        assign.push(" last_modified = null ");
+        reschedule = true;
+    }
+    if let Some(active) = fields.active {
+        // Turning a hydrant back on (maybe after it gave up) starts it over with a clean slate.
+        if active && !hydrant.active {
+            assign.push(" failure_count = 0 ");
+            assign.push(" last_error = null ");
+            reschedule = true;
+        }
+        // Once its owner turns it on or off, it no longer matters why the hydrant stopped itself.
+        if active != hydrant.active {
+            assign.push(" deactivated_for = null ");
+        }
+        assign.push(" active = ");
+        assign.push_bind_unseparated(active);
+        do_assign = true;
+    }
+    if let Some(fetch_interval_minutes) = fields.fetch_interval_minutes {
+        if fetch_interval_minutes != hydrant.fetch_interval_minutes {
+            reschedule = true;
+        }
+        assign.push(" fetch_interval_minutes = ");
+        assign.push_bind_unseparated(fetch_interval_minutes);
+        do_assign = true;
+    }
+    if let Some(rules) = fields.rules {
+        assign.push(" include_rules = ");
+        assign.push_bind_unseparated(rules.include);
+        assign.push(" exclude_rules = ");
+        assign.push_bind_unseparated(rules.exclude);
+        do_assign = true;
+    }
+    if let Some(policy) = fields.policy {
+        assign.push(" first_fetch_limit = ");
+        assign.push_bind_unseparated(policy.first_fetch_limit);
+        assign.push(" max_drops_per_fetch = ");
+        assign.push_bind_unseparated(policy.max_drops_per_fetch);
+        assign.push(" backfill_read = ");
+        assign.push_bind_unseparated(policy.backfill_read);
+        do_assign = true;
+    }
+    if reschedule {
+        assign.push(" next_attempt_at = null ");
+    }
+
+    conn.transaction(|tx| {
+        Box::pin(async move {
+            let tags = if let Some(selectors) = fields.tags {
+                let mut tags = Vec::new();
+                let mut tag_ids = Vec::new();
+                for sel in selectors {
+                    let tag = find_or_create_tag(&mut *tx, &user, sel).await?;
+                    tag_ids.push(tag.id);
+                    tags.push(tag);
+                }
+
+                // Lifetime issues with `query` and `assign` make it difficult to pass the builder
+                // state into the closure. If we're already doing an assignment, push an empty
+                // string onto the query to sync the separator state.
+                let mut assign = query.separated(" , ");
+                if do_assign {
+                    assign.push("");
+                }
+                assign.push(" tag_ids = ");
+                assign.push_bind_unseparated(tag_ids);
+                do_assign = true;
+
+                tags
+            } else {
+                find_tags(&mut *tx, &user, &hydrant.tag_ids).await?
+            };
+
+            query.push(" where id = ");
+            query.push_bind(hydrant.id);
+            query.push(" and user_id = ");
+            query.push_bind(user.id);
+            query.push(" returning *");
+
+            if do_assign {
+                let hydrant: models::Hydrant = query.build_query_as().fetch_one(&mut *tx).await?;
+                Ok(Hydrant { hydrant, tags })
+            } else {
+                find_hydrant(&mut *tx, &user, hydrant.id).await
+            }
+        })
+    })
+    .await
+}
+
+// What happens to a hydrant's drops when it's deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropDisposal {
+    // Keep all of them, just without a hydrant.
+    #[default]
+    Detach,
+
+    Delete,
+
+    // Delete the drops nobody has looked at yet, and keep the rest.
+    DeleteUnread,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DropCounts {
+    pub unread: i64,
+    pub read: i64,
+    pub saved: i64,
+}
+
+impl DropCounts {
+    pub fn total(&self) -> i64 {
+        self.unread + self.read + self.saved
+    }
+}
+
+pub async fn count_hydrant_drops(
+    conn: impl PgExecutor<'_>,
+    hydrant: &models::Hydrant,
+) -> sqlx::Result<DropCounts> {
+    let row = sqlx::query!(
+        r#"
+        select
+            count(*) filter (where status = 'unread') as "unread!"
+          , count(*) filter (where status = 'read') as "read!"
+          , count(*) filter (where status = 'saved') as "saved!"
+        from drops
+        where hydrant_id = $1
+        "#,
+        hydrant.id,
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(DropCounts {
+        unread: row.unread,
+        read: row.read,
+        saved: row.saved,
+    })
+}
+
+pub async fn delete_hydrant(
+    conn: &mut PgConnection,
+    user: &models::User,
+    hydrant: models::Hydrant,
+    drops: DropDisposal,
+) -> sqlx::Result<models::Hydrant> {
+    let user = user.clone();
+
+    conn.transaction(|tx| {
+        Box::pin(async move {
+            let statuses = match drops {
+                DropDisposal::Detach => vec![],
+                DropDisposal::Delete => {
+                    vec![DropStatus::Unread, DropStatus::Read, DropStatus::Saved]
+                }
+                DropDisposal::DeleteUnread => vec![DropStatus::Unread],
+            };
+            let statuses: Vec<String> = statuses.iter().map(ToString::to_string).collect();
+
+            sqlx::query!(
+                "
+                delete from drop_tags
+                where drop_id in (
+                    select id
+                    from drops
+                    where hydrant_id = $1
+                    and user_id = $2
+                    and status::text = any($3)
+                )
+                ",
+                hydrant.id,
+                user.id,
+                &statuses,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "
+                delete from drops
+                where hydrant_id = $1
+                and user_id = $2
+                and status::text = any($3)
+                ",
+                hydrant.id,
+                user.id,
+                &statuses,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            // Whatever is left has to let go of the hydrant before it can be deleted.
+            sqlx::query!(
+                "
+                update drops
+                set hydrant_id = null
+                where hydrant_id = $1
+                and user_id = $2
+                ",
+                hydrant.id,
+                user.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query_as(
+                "
+                delete from hydrants
+                where id = $1
+                and user_id = $2
+                returning *
+                ",
+            )
+            .bind(hydrant.id)
+            .bind(user.id)
+            .fetch_one(&mut *tx)
+            .await
+        })
+    })
+    .await
+}
+
+pub fn export_opml(hydrants: &[Hydrant]) -> Result<String, opml::Error> {
+    // Other feed readers would only choke on anything but feeds.
+    let outlines: Vec<opml::Outline> = hydrants
+        .iter()
+        .filter(|h| h.hydrant.source_kind == HydrantSourceKind::Feed)
+        .map(|h| opml::Outline {
+            title: h.hydrant.name.clone(),
+            xml_url: h.hydrant.url.clone(),
+            active: h.hydrant.active,
+            categories: h.tags.iter().map(|t| t.name.clone()).collect(),
+        })
+        .collect();
+
+    opml::write("Metagram Firehose hydrants", &outlines)
+}
+
+#[derive(Debug)]
+pub enum ImportStatus {
+    Created(Box<Hydrant>),
+    Duplicate,
+    Invalid(String),
+}
+
+#[derive(Debug)]
+pub struct ImportOutcome {
+    pub title: String,
+    pub url: Option<String>,
+    pub status: ImportStatus,
+}
+
+// Create a hydrant for each feed in an OPML document. Problems with individual outlines are
+// reported in their outcomes instead of stopping the whole import.
+pub async fn import_opml(
+    conn: &mut PgConnection,
+    user: &models::User,
+    outlines: Vec<Result<opml::Outline, opml::InvalidOutline>>,
+) -> anyhow::Result<Vec<ImportOutcome>> {
+    let mut urls: HashSet<String> = list_hydrants(&mut *conn, user)
+        .await?
+        .into_iter()
+        .map(|h| h.hydrant.url)
+        .collect();
+
+    // Match categories to tags by name, remembering any that get created along the way so that
+    // each one is only created once.
+    let mut tag_ids: HashMap<String, Uuid> = list_tags(&mut *conn, user)
+        .await?
+        .into_iter()
+        .map(|t| (t.name.to_lowercase(), t.id))
+        .collect();
+
+    let mut outcomes = Vec::new();
+    for outline in outlines {
+        let outline = match outline {
+            Ok(outline) => outline,
+            Err(invalid) => {
+                outcomes.push(ImportOutcome {
+                    title: invalid.title.unwrap_or_default(),
+                    url: None,
+                    status: ImportStatus::Invalid(invalid.reason),
+                });
+                continue;
+            }
+        };
+
+        if !urls.insert(outline.xml_url.clone()) {
+            outcomes.push(ImportOutcome {
+                title: outline.title,
+                url: Some(outline.xml_url),
+                status: ImportStatus::Duplicate,
+            });
+            continue;
+        }
+
+        let selectors = outline
+            .categories
+            .iter()
+            .map(|name| match tag_ids.get(&name.to_lowercase()) {
+                Some(id) => TagSelector::Find { id: *id },
+                None => TagSelector::Create {
+                    name: name.clone(),
+                    color: Tag::DEFAULT_COLOR.to_string(),
+                },
+            })
+            .collect();
+
+        let res = create_hydrant(
+            &mut *conn,
+            user,
+            NewHydrant {
+                name: outline.title.clone(),
+                url: outline.xml_url.clone(),
+                active: outline.active,
+                policy: IngestionPolicy::for_new_hydrant(),
+                tags: Some(selectors),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let status = match res {
+            Ok(hydrant) => {
+                for tag in &hydrant.tags {
+                    tag_ids.insert(tag.name.to_lowercase(), tag.id);
+                }
+                ImportStatus::Created(Box::new(hydrant))
+            }
+            Err(err) => {
+                tracing::error!({ ?err, ?outline }, "could not import hydrant");
+                ImportStatus::Invalid("could not save this hydrant".to_string())
+            }
+        };
+
+        outcomes.push(ImportOutcome {
+            title: outline.title,
+            url: Some(outline.xml_url),
+            status,
+        });
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::SubsecRound;
+    use sqlx::{Connection, PgConnection};
+
+    use super::*;
+    use crate::auth;
+
+    // TODO: Make this a test transaction and roll it back on pass.
+    async fn test_conn() -> sqlx::Result<PgConnection> {
+        let url = std::env::var("TEST_DATABASE_URL").unwrap();
+        PgConnection::connect(&url).await
+    }
+
+    async fn test_user(conn: &mut PgConnection) -> sqlx::Result<models::User> {
+        let stytch_user_id: String = uuid::Uuid::new_v4().to_string();
+        auth::create_user(conn, stytch_user_id).await
+    }
+
+    fn lorem_rss() -> anyhow::Result<url::Url> {
+        Ok(std::env::var("LOREM_RSS_URL")?.parse()?)
+    }
+
+    // Serve the router on a random local port for tests that need to control the responses.
+    fn test_server(router: axum::Router) -> url::Url {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(router.into_make_service());
+        tokio::spawn(server);
+
+        format!("http://{}/", addr).parse().unwrap()
+    }
+
+    const BLOG_HTML: &str = r#"
+        <html>
+          <head><link rel="alternate" type="application/rss+xml" href="/feed"></head>
+          <body>Blog</body>
+        </html>
+    "#;
+
+    const BLOGS_HTML: &str = r#"
+        <html>
+          <head>
+            <link rel="alternate" type="application/rss+xml" href="/feed">
+            <link rel="alternate" type="application/atom+xml" href="/atom">
+          </head>
+          <body>Blog</body>
+        </html>
+    "#;
+
+    const UNDATED_RSS: &str = r#"<?xml version="1.0"?>
+        <rss version="2.0">
+          <channel>
+            <title>Undated</title>
+            <link>https://example.com</link>
+            <description>Items without dates</description>
+            <item><link>https://example.com/undated/1</link></item>
+            <item><link>https://example.com/undated/2</link></item>
+          </channel>
+        </rss>
+    "#;
+
+    #[tokio::test]
+    async fn minimal_drop() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let created = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            None,
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let found = find_drop(&mut tx, &user, created.drop.id).await.unwrap();
+        assert_eq!(found, created);
+    }
+
+    #[tokio::test]
+    async fn maximal_drop() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let coffee = create_tag(&mut tx, &user, "Coffee", "#c0ffee")
+            .await
+            .unwrap();
+
+        let created = create_drop(
+            &mut tx,
+            &user,
+            Some("Lorem Ipsum".to_string()),
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            Some(vec![
+                TagSelector::Find { id: coffee.id },
+                TagSelector::Create {
+                    name: "ABC".to_string(),
+                    color: models::Tag::DEFAULT_COLOR.to_string(),
+                },
+            ]),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let found = find_drop(&mut tx, &user, created.drop.id).await.unwrap();
+        assert_eq!(found, created);
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["ABC", "Coffee"]);
+    }
+
+    #[tokio::test]
+    async fn update_drop_fields() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let drop = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            None,
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let fields = DropFields {
+            title: Some("Dolor Sit".to_string()),
+            url: Some("https://example.com/dolor-sit".to_string()),
+            ..Default::default()
+        };
+        let tags = None;
+
+        let updated = update_drop(&mut tx, &user, &drop.drop, fields, tags)
+            .await
+            .unwrap();
+
+        let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
+        assert_eq!(found, updated);
+
+        assert_eq!(found.drop.title, Some("Dolor Sit".to_string()));
+        assert_eq!(found.drop.url, "https://example.com/dolor-sit".to_string(),);
+    }
+
+    #[tokio::test]
+    async fn drop_notes_and_highlights() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let drop = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+        assert_eq!(drop.drop.notes, None);
+        assert!(drop.drop.highlights.is_empty());
+
+        let fields = DropFields {
+            notes: Some(Some("Saved for the *tomatoes* part.".to_string())),
+            highlights: Some(vec!["Lorem ipsum.".to_string()]),
+            ..Default::default()
+        };
+        let updated = update_drop(&mut tx, &user, &drop.drop, fields, None)
+            .await
+            .unwrap();
+
+        let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
+        assert_eq!(found, updated);
+        assert_eq!(
+            found.drop.notes,
+            Some("Saved for the *tomatoes* part.".to_string())
+        );
+        assert_eq!(found.drop.highlights, vec!["Lorem ipsum.".to_string()]);
+
+        // Notes are searchable.
+        let search = DropFilters {
+            query: Some("tomatoes".to_string()),
+            ..Default::default()
+        };
+        let drops = list_drops(&mut tx, &user, search, None).await.unwrap();
+        assert_eq!(drops, vec![found.clone()]);
+
+        // Merging a drop into this one keeps its notes and highlights too.
+        let other = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/other".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+
+        let fields = DropFields {
+            url: Some("https://example.com/lorem-ipsum".to_string()),
+            notes: Some(Some("And the peppers.".to_string())),
+            highlights: Some(vec!["Lorem ipsum.".to_string(), "Dolor sit.".to_string()]),
+            ..Default::default()
+        };
+        let merged = update_drop(&mut tx, &user, &other.drop, fields, None)
+            .await
+            .unwrap();
+        assert_eq!(merged.drop.id, drop.drop.id);
+        assert_eq!(
+            merged.drop.notes,
+            Some("Saved for the *tomatoes* part.\n\nAnd the peppers.".to_string())
+        );
+        assert_eq!(
+            merged.drop.highlights,
+            vec!["Lorem ipsum.".to_string(), "Dolor sit.".to_string()]
+        );
+
+        // Clearing the notes leaves nothing behind.
+        let fields = DropFields {
+            notes: Some(None),
+            highlights: Some(Vec::new()),
+            ..Default::default()
+        };
+        let cleared = update_drop(&mut tx, &user, &merged.drop, fields, None)
+            .await
+            .unwrap();
+        assert_eq!(cleared.drop.notes, None);
+        assert!(cleared.drop.highlights.is_empty());
+    }
+
+    #[tokio::test]
+    async fn update_drop_tags() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let coffee = create_tag(&mut tx, &user, "Coffee", "#c0ffee")
+            .await
+            .unwrap();
+
+        let drop = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            Some(vec![TagSelector::Find { id: coffee.id }]),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let fields = Default::default();
+        let tags = Some(vec![TagSelector::Create {
+            name: "ABC".to_string(),
+            color: models::Tag::DEFAULT_COLOR.to_string(),
+        }]);
+
+        let updated = update_drop(&mut tx, &user, &drop.drop, fields, tags)
+            .await
+            .unwrap();
+
+        let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
+
+        assert_eq!(found.drop, updated.drop);
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["ABC"]);
+    }
+
+    #[tokio::test]
+    async fn change_drop_status() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let now = chrono::Utc::now();
+
+        let drop = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/lorem-ipsum".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+
+        let drop_id = drop.drop.id;
+
+        let moved = move_drop(
+            &mut tx,
+            drop,
+            DropStatus::Read,
+            now + chrono::Duration::minutes(5),
+        )
+        .await
+        .unwrap();
+
+        let found = find_drop(&mut tx, &user, drop_id).await.unwrap();
+        assert_eq!(found, moved);
+
+        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
+        // number of subsecond digits (6).
+        assert_eq!(
+            found.drop.moved_at,
+            (now + chrono::Duration::minutes(5))
+                .naive_utc()
+                .trunc_subsecs(6)
+        );
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_status() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let mut unread = Vec::new();
+        let mut read = Vec::new();
+        for i in 0..10 {
+            let mut drop = create_drop(
+                &mut tx,
+                &user,
+                None,
+                format!("https://example.com/filtering/{}", i),
+                None,
+                None,
+                now + chrono::Duration::seconds(i.try_into().unwrap()),
+            )
+            .await
+            .unwrap();
+
+            if i % 2 == 0 {
+                drop = move_drop(&mut tx, drop, DropStatus::Read, now)
+                    .await
+                    .unwrap();
+                read.push(drop);
+            } else {
+                unread.push(drop);
+            }
+        }
+
+        let found_unread = list_drops(
+            &mut tx,
+            &user,
+            DropFilters {
+                status: Some(DropStatus::Unread),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(found_unread, unread);
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_tags() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        // Set up state that looks like this:
+        //
+        // D0: {T0}
+        // D1: {T0, T1}
+        // D2: {T0, T1, T2}
+
+        let mut tags = Vec::new();
+        for i in 0..3 {
+            let tag = create_tag(&mut tx, &user, &i.to_string(), models::Tag::DEFAULT_COLOR)
+                .await
+                .unwrap();
+
+            tags.push(tag);
+        }
+
+        let mut drops = Vec::new();
+        for i in 0..3 {
+            let sel = tags[0..i + 1]
+                .iter()
+                .cloned()
+                .map(|tag| TagSelector::Find { id: tag.id })
+                .collect();
+
+            let drop = create_drop(
+                &mut tx,
+                &user,
+                None,
+                format!("https://example.com/filtering/{}", i),
+                None,
+                Some(sel),
+                now + chrono::Duration::seconds(i.try_into().unwrap()),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(drop.tags.len(), i + 1);
+
+            drops.push(drop);
+        }
+
+        // Each tag selects suffix slices from the drops:
+        //
+        // T0: {D0, D1, D2}
+        // T1: {    D1, D2}
+        // T2: {        D2}
+        for i in 0..3 {
+            let found = list_drops(
+                &mut tx,
+                &user,
+                DropFilters {
+                    tags: Some(vec![tags[i].clone()]),
+                    ..Default::default()
+                },
+                Some(100),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(found.len(), 3 - i);
+
+            let expected = &drops[i..];
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_hydrant() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let mut hydrants = Vec::new();
+        for name in ["Lorem", "Ipsum"] {
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: name.to_string(),
+                    url: format!("https://example.com/{}.xml", name),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            hydrants.push(hydrant);
+        }
+
+        let mut drops = Vec::new();
+        for (i, hydrant_id) in [
+            Some(hydrants[0].hydrant.id),
+            Some(hydrants[1].hydrant.id),
+            None,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let drop = create_drop(
+                &mut tx,
+                &user,
+                None,
+                format!("https://example.com/provenance/{}", i),
+                hydrant_id,
+                None,
+                now,
+            )
+            .await
+            .unwrap();
+
+            let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
+            assert_eq!(found, drop);
+
+            drops.push(drop);
+        }
+
+        assert_eq!(
+            drops[0].hydrant,
+            Some(DropHydrant {
+                id: hydrants[0].hydrant.id,
+                name: "Lorem".to_string(),
+            })
+        );
+        assert_eq!(drops[2].hydrant, None);
+
+        let found = list_drops(
+            &mut tx,
+            &user,
+            DropFilters {
+                hydrant_id: Some(hydrants[1].hydrant.id),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(found, vec![drops[1].clone()]);
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_query() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let tag = create_tag(&mut tx, &user, "Gardening", "#00ff00")
+            .await
+            .unwrap();
+
+        let mut drops = Vec::new();
+        for (title, url, tags) in [
+            (Some("Lorem Ipsum"), "https://example.com/one", None),
+            (None, "https://www.dolor.net/sit-amet?page=2", None),
+            (
+                Some("Consectetur"),
+                "https://example.org/three",
+                Some(vec![TagSelector::Find { id: tag.id }]),
+            ),
+        ] {
+            let drop = create_drop(
+                &mut tx,
+                &user,
+                title.map(String::from),
+                url.to_string(),
+                None,
+                tags,
+                now,
+            )
+            .await
+            .unwrap();
+
+            drops.push(drop);
+        }
+
+        let search = |terms: &str| DropFilters {
+            query: Some(terms.to_string()),
+            ..Default::default()
+        };
+
+        // Titles
+        let found = list_drops(&mut tx, &user, search("ipsum"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[0].clone()]);
+
+        // Domains, with or without "www."
+        let found = list_drops(&mut tx, &user, search("dolor.net"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[1].clone()]);
+
+        let found = list_drops(&mut tx, &user, search("dolor"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[1].clone()]);
+
+        // The rest of the URL
+        let found = list_drops(&mut tx, &user, search("amet"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[1].clone()]);
+
+        // Tags
+        let found = list_drops(&mut tx, &user, search("gardening"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[2].clone()]);
+
+        let found = list_drops(&mut tx, &user, search("example -lorem"), None)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![drops[2].clone()]);
+
+        let counts = count_drops(&mut tx, &user, search("example"))
+            .await
+            .unwrap();
+        assert_eq!(
+            counts,
+            DropCounts {
+                unread: 2,
+                read: 0,
+                saved: 0,
+            }
+        );
+
+        let tag_counts = count_drop_tags(&mut tx, &user, search("example"))
+            .await
+            .unwrap();
+        assert_eq!(
+            tag_counts,
+            vec![TagCount {
+                tag: tag.clone(),
+                drops: 1,
+            }]
+        );
+
+        // Every match can be reached a page at a time.
+        let first = list_drops_page(&mut tx, &user, search("example"), None, 1)
+            .await
+            .unwrap();
+        let second = list_drops_page(&mut tx, &user, search("example"), first.next, 1)
+            .await
+            .unwrap();
+        assert_eq!(second.next, None);
+
+        let mut found: Vec<Uuid> = [first.drops, second.drops]
+            .concat()
+            .into_iter()
+            .map(|drop| drop.drop.id)
+            .collect();
+        found.sort();
+        let mut expected = vec![drops[0].drop.id, drops[2].drop.id];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_reading_time() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let mut drops = Vec::new();
+        for (i, (url, word_count)) in [
+            ("https://example.com/short", Some(150)),
+            ("https://example.com/long", Some(2500)),
+            ("https://example.com/unknown", None),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let moved_at = now + chrono::Duration::seconds(i as i64);
+            let drop = create_drop(&mut tx, &user, None, url.to_string(), None, None, moved_at)
+                .await
+                .unwrap();
+
+            if let Some(word_count) = word_count {
+                sqlx::query!(
+                    "insert into drop_articles (drop_id, html, word_count) values ($1, '', $2)",
+                    drop.drop.id,
+                    word_count,
+                )
+                .execute(&mut tx)
+                .await
+                .unwrap();
+            }
+
+            drops.push(find_drop(&mut tx, &user, drop.drop.id).await.unwrap());
+        }
+
+        assert_eq!(drops[0].word_count, Some(150));
+        assert_eq!(drops[2].word_count, None);
+
+        let quick = |minutes: i32| DropFilters {
+            max_reading_minutes: Some(minutes),
+            ..Default::default()
+        };
+
+        let found = list_drops(&mut tx, &user, quick(5), None).await.unwrap();
+        assert_eq!(found, vec![drops[0].clone()]);
+
+        let found = list_drops(&mut tx, &user, quick(15), None).await.unwrap();
+        assert_eq!(found, vec![drops[0].clone(), drops[1].clone()]);
+
+        let found = list_drops(&mut tx, &user, Default::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(found, drops);
+    }
+
+    #[tokio::test]
+    async fn list_drops_by_page() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let mut drops = Vec::new();
+        for i in 0..5 {
+            let drop = create_drop(
+                &mut tx,
+                &user,
+                None,
+                format!("https://example.com/page/{}", i),
+                None,
+                None,
+                now + chrono::Duration::seconds(i),
+            )
+            .await
+            .unwrap();
+
+            drops.push(drop);
+        }
+
+        let cursor = |i: usize| Cursor::new(&drops[i].drop);
+
+        let first = list_drops_page(&mut tx, &user, Default::default(), None, 2)
+            .await
+            .unwrap();
+        assert_eq!(first.drops, drops[0..2]);
+        assert_eq!(first.prev, None);
+        assert_eq!(first.next, Some(PageCursor::After(cursor(1))));
+
+        let second = list_drops_page(&mut tx, &user, Default::default(), first.next, 2)
+            .await
+            .unwrap();
+        assert_eq!(second.drops, drops[2..4]);
+        assert_eq!(second.prev, Some(PageCursor::Before(cursor(2))));
+        assert_eq!(second.next, Some(PageCursor::After(cursor(3))));
+
+        let last = list_drops_page(&mut tx, &user, Default::default(), second.next, 2)
+            .await
+            .unwrap();
+        assert_eq!(last.drops, drops[4..]);
+        assert_eq!(last.prev, Some(PageCursor::Before(cursor(4))));
+        assert_eq!(last.next, None);
+
+        // Going backward gets the same pages.
+        let back = list_drops_page(&mut tx, &user, Default::default(), last.prev, 2)
+            .await
+            .unwrap();
+        assert_eq!(back, second);
+
+        let back = list_drops_page(&mut tx, &user, Default::default(), back.prev, 2)
+            .await
+            .unwrap();
+        assert_eq!(back.drops, first.drops);
+        assert_eq!(back.prev, None);
+        assert_eq!(back.next, first.next);
+
+        // Cursors make it through URLs intact.
+        assert_eq!(cursor(3).to_string().parse::<Cursor>().unwrap(), cursor(3));
+        assert!("nope".parse::<Cursor>().is_err());
+    }
+
+    #[tokio::test]
+    async fn merge_duplicate_drops() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let lorem = create_tag(&mut tx, &user, "Lorem", "#ff0000")
+            .await
+            .unwrap();
+        let ipsum = create_tag(&mut tx, &user, "Ipsum", "#0000ff")
+            .await
+            .unwrap();
+
+        let original = create_drop(
+            &mut tx,
+            &user,
+            Some("Original".to_string()),
+            "https://Example.com/article?utm_source=rss#comments".to_string(),
+            None,
+            Some(vec![TagSelector::Find { id: lorem.id }]),
+            now,
+        )
+        .await
+        .unwrap();
+        let original = move_drop(&mut tx, original, DropStatus::Read, now)
+            .await
+            .unwrap();
+
+        // Saving it again only adds the new tags.
+        let saved = save_drop(
+            &mut tx,
+            &user,
+            Some("Again".to_string()),
+            "https://example.com/article".to_string(),
+            None,
+            Some(vec![TagSelector::Find { id: ipsum.id }]),
+            now,
+        )
+        .await
+        .unwrap();
+
+        let merged = match saved {
+            SavedDrop::Merged(drop) => drop,
+            SavedDrop::New(drop) => panic!("expected a merge, got {:?}", drop),
+        };
+        assert_eq!(merged.drop, original.drop);
+        assert_eq!(merged.tags, vec![ipsum.clone(), lorem.clone()]);
+
+        // Changing another drop's URL to the same page merges that one in too.
+        let other = create_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/other".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+
+        let fields = DropFields {
+            url: Some("https://example.com/article?fbclid=abc".to_string()),
+            ..Default::default()
+        };
+        let updated = update_drop(&mut tx, &user, &other.drop, fields, Some(vec![]))
+            .await
+            .unwrap();
+        assert_eq!(updated.drop, original.drop);
+
+        let drops = list_drops(&mut tx, &user, Default::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(drops, vec![updated]);
+
+        // Other people can save the same page.
+        let someone = test_user(&mut tx).await.unwrap();
+        let saved = save_drop(
+            &mut tx,
+            &someone,
+            None,
+            "https://example.com/article".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(saved, SavedDrop::New(_)));
+    }
+
+    #[tokio::test]
+    async fn canonicalize_old_drops() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let lorem = create_tag(&mut tx, &user, "Lorem", "#ff0000")
+            .await
+            .unwrap();
+
+        // Drops from before canonical URLs only have the URL they were saved with.
+        let mut drops = Vec::new();
+        for (i, (url, tags)) in [
+            ("https://Example.com/article?utm_source=rss", None),
+            (
+                "https://example.com/article#comments",
+                Some(vec![TagSelector::Find { id: lorem.id }]),
+            ),
+            ("https://example.com/other", None),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let drop = create_drop(
+                &mut tx,
+                &user,
+                None,
+                format!("https://example.com/{}", i),
+                None,
+                tags,
+                now,
+            )
+            .await
+            .unwrap();
+
+            sqlx::query(
+                "update drops set url = $1, canonical_url = null, created_at = $2 where id = $3",
+            )
+            .bind(url)
+            .bind((now + chrono::Duration::seconds(i as i64)).naive_utc())
+            .bind(drop.drop.id)
+            .execute(&mut tx)
+            .await
+            .unwrap();
+
+            drops.push(drop);
+        }
+
+        let done = canonicalize_drops(&mut tx, 2).await.unwrap();
+        assert_eq!(done, 2);
+        let done = canonicalize_drops(&mut tx, 2).await.unwrap();
+        assert_eq!(done, 1);
+        let done = canonicalize_drops(&mut tx, 2).await.unwrap();
+        assert_eq!(done, 0);
+
+        // The newer duplicate was merged into the older one, tags and all.
+        let mut found = list_drops(&mut tx, &user, Default::default(), None)
+            .await
+            .unwrap();
+        found.sort_by_key(|drop| drop.drop.created_at);
+        let ids: Vec<Uuid> = found.iter().map(|drop| drop.drop.id).collect();
+        assert_eq!(ids, vec![drops[0].drop.id, drops[2].drop.id]);
+        assert_eq!(found[0].tags, vec![lorem.clone()]);
+
+        // And saving the same page again finds it.
+        let saved = save_drop(
+            &mut tx,
+            &user,
+            None,
+            "https://example.com/article".to_string(),
+            None,
+            None,
+            now,
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved, SavedDrop::Merged(found[0].clone()));
+    }
+
+    #[tokio::test]
+    async fn update_tag_fields() {
+        let mut conn = test_conn().await.unwrap();
+        let mut conn = conn.begin().await.unwrap();
+
+        let user = test_user(&mut conn).await.unwrap();
+
+        let tag = create_tag(&mut conn, &user, "Work", "#0000ff")
+            .await
+            .unwrap();
+
+        let tag_id = tag.id;
+
+        let fields = TagFields {
+            name: Some("Play".to_string()),
+            color: Some("#00ff00".to_string()),
+        };
+
+        let updated = update_tag(&mut conn, &user, tag, fields).await.unwrap();
+
+        let found = find_tag(&mut conn, &user, tag_id).await.unwrap();
+        assert_eq!(found, updated);
+
+        assert_eq!(found.name, "Play".to_string());
+        assert_eq!(found.color, "#00ff00".to_string());
+    }
+
+    #[tokio::test]
+    async fn empty_stream() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let res = create_stream(&mut tx, &user, "Empty", &[], None).await;
+        let err = res.unwrap_err();
+        assert!(matches!(err, Error::Stream(StreamError::NoTags)));
+    }
+
+    #[tokio::test]
+    async fn stream_with_tags() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+
+        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+
+        let created = create_stream(&mut tx, &user, "Colors", &vec![red, blue], None)
+            .await
+            .unwrap();
+
+        let found = find_stream(&mut tx, &user, created.stream.id)
+            .await
+            .unwrap();
+        assert_eq!(created, found);
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["Blue", "Red"]);
+    }
+
+    #[tokio::test]
+    async fn change_stream_fields() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+
+        let stream = create_stream(&mut tx, &user, "Oops!", &[red], None)
+            .await
+            .unwrap();
+
+        let green = create_tag(&mut tx, &user, "Green", "#00ff00")
+            .await
+            .unwrap();
+
+        let fields = StreamFields {
+            name: Some("Yay!".to_string()),
+            tag_ids: Some(vec![green.id]),
+            max_reading_minutes: Some(Some(5)),
+        };
+
+        let updated = update_stream(&mut tx, &user, &stream.stream, fields)
+            .await
+            .unwrap();
+
+        let found = find_stream(&mut tx, &user, stream.stream.id).await.unwrap();
+        assert_eq!(updated, found);
+
+        assert_eq!(found.stream.name, "Yay!".to_string());
+        assert_eq!(found.stream.max_reading_minutes, Some(5));
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["Green"]);
+    }
+
+    #[tokio::test]
+    async fn list_streams_default() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let found = list_streams(&mut tx, &user).await.unwrap();
+
+        let expected = vec![
+            Stream::Status(StatusStream::new(DropStatus::Unread)),
+            Stream::Status(StatusStream::new(DropStatus::Read)),
+            Stream::Status(StatusStream::new(DropStatus::Saved)),
+        ];
+
+        assert_eq!(found, expected);
+    }
+
+    #[tokio::test]
+    async fn list_streams_custom() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+
+        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+
+        let colors = create_stream(&mut tx, &user, "Colors", &vec![red, blue.clone()], None)
+            .await
+            .unwrap();
+
+        let only_blue = create_stream(&mut tx, &user, "Only Blue", &[blue.clone()], None)
+            .await
+            .unwrap();
+
+        let found = list_streams(&mut tx, &user).await.unwrap();
+
+        let expected = vec![
+            Stream::Status(StatusStream::new(DropStatus::Unread)),
+            Stream::Status(StatusStream::new(DropStatus::Read)),
+            Stream::Status(StatusStream::new(DropStatus::Saved)),
+            Stream::Custom(CustomStream { ..colors }),
+            Stream::Custom(CustomStream { ..only_blue }),
+        ];
+        assert_eq!(found, expected);
+    }
+
+    #[tokio::test]
+    async fn simple_hydrant() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let created = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Simple".to_string(),
+                url: "https://example.com/simple".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let found = find_hydrant(&mut tx, &user, created.hydrant.id)
+            .await
+            .unwrap();
+        assert_eq!(created, found);
+
+        assert_eq!(found.hydrant.name, "Simple".to_string());
+        assert_eq!(found.hydrant.url, "https://example.com/simple".to_string());
+        assert!(found.hydrant.active);
+        assert_eq!(found.tags, vec![]);
+    }
+
+    #[tokio::test]
+    async fn tagged_hydrant() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+
+        let created = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Painted".to_string(),
+                url: "https://example.com/painted".to_string(),
+                tags: Some(vec![
+                    TagSelector::Find { id: red.id },
+                    TagSelector::Create {
+                        name: "Blue".to_string(),
+                        color: "#0000ff".to_string(),
+                    },
+                ]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let found = find_hydrant(&mut tx, &user, created.hydrant.id)
+            .await
+            .unwrap();
+        assert_eq!(created, found);
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["Blue", "Red"]);
     }
-    if let Some(active) = fields.active {
-        assign.push(" active = ");
-        assign.push_bind_unseparated(active);
-        do_assign = true;
+
+    #[tokio::test]
+    async fn change_hydrant_fields() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let black = create_tag(&mut tx, &user, "Black", "#ff0000")
+            .await
+            .unwrap();
+
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "The Rolling Stones".to_string(),
+                url: "https://example.com/painted".to_string(),
+                tags: Some(vec![TagSelector::Find { id: black.id }]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+
+        let fields = HydrantFields {
+            name: Some("Eiffel 65".to_string()),
+            url: Some("https://example.com/blue".to_string()),
+            source_kind: Some(HydrantSourceKind::Sitemap),
+            active: Some(false),
+            fetch_interval_minutes: Some(15),
+            rules: Some(FilterRules {
+                include: vec![],
+                exclude: vec!["domain:example.org".to_string()],
+            }),
+            policy: Some(IngestionPolicy {
+                first_fetch_limit: None,
+                max_drops_per_fetch: Some(5),
+                backfill_read: true,
+            }),
+            tags: Some(vec![TagSelector::Find { id: blue.id }]),
+        };
+
+        let updated = update_hydrant(&mut tx, &user, &hydrant.hydrant, fields)
+            .await
+            .unwrap();
+
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+            .await
+            .unwrap();
+        assert_eq!(updated, found);
+
+        assert_eq!(found.hydrant.name, "Eiffel 65".to_string());
+        assert_eq!(found.hydrant.url, "https://example.com/blue".to_string());
+        assert_eq!(found.hydrant.source_kind, HydrantSourceKind::Sitemap);
+        assert!(!found.hydrant.active);
+        assert_eq!(found.hydrant.fetch_interval_minutes, 15);
+        assert_eq!(found.hydrant.exclude_rules, vec!["domain:example.org"]);
+        assert_eq!(found.hydrant.first_fetch_limit, None);
+        assert_eq!(found.hydrant.max_drops_per_fetch, Some(5));
+        assert!(found.hydrant.backfill_read);
+
+        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
+        assert_eq!(tag_names, vec!["Blue"]);
     }
 
-    conn.transaction(|tx| {
-        Box::pin(async move {
-            let tags = if let Some(selectors) = fields.tags {
-                let mut tags = Vec::new();
-                let mut tag_ids = Vec::new();
-                for sel in selectors {
-                    let tag = find_or_create_tag(&mut *tx, &user, sel).await?;
-                    tag_ids.push(tag.id);
-                    tags.push(tag);
+    #[tokio::test]
+    async fn delete_hydrant_drops() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let tag = create_tag(&mut tx, &user, "Doomed", Tag::DEFAULT_COLOR)
+            .await
+            .unwrap();
+
+        for (disposal, remaining) in [
+            (DropDisposal::Detach, 3),
+            (DropDisposal::DeleteUnread, 2),
+            (DropDisposal::Delete, 0),
+        ] {
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: "Doomed".to_string(),
+                    url: "https://example.com/doomed".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            for status in [DropStatus::Unread, DropStatus::Read, DropStatus::Saved] {
+                let tags = Some(vec![TagSelector::Find { id: tag.id }]);
+                let url = format!("https://example.com/{}", status);
+                let drop = create_drop(
+                    &mut tx,
+                    &user,
+                    None,
+                    url,
+                    Some(hydrant.hydrant.id),
+                    tags,
+                    now,
+                )
+                .await
+                .unwrap();
+                move_drop(&mut tx, drop, status, now).await.unwrap();
+            }
+
+            let counts = count_hydrant_drops(&mut tx, &hydrant.hydrant)
+                .await
+                .unwrap();
+            assert_eq!(
+                counts,
+                DropCounts {
+                    unread: 1,
+                    read: 1,
+                    saved: 1,
                 }
+            );
 
-                // Lifetime issues with `query` and `assign` make it difficult to pass the builder
-                // state into the closure. If we're already doing an assignment, push an empty
-                // string onto the query to sync the separator state.
-                let mut assign = query.separated(" , ");
-                if do_assign {
-                    assign.push("");
+            delete_hydrant(&mut tx, &user, hydrant.hydrant.clone(), disposal)
+                .await
+                .unwrap();
+
+            let kept = list_drops(&mut tx, &user, Default::default(), Some(100))
+                .await
+                .unwrap();
+            assert_eq!(kept.len(), remaining, "{:?}", disposal);
+            for drop in kept {
+                assert_eq!(drop.drop.hydrant_id, None);
+                assert_eq!(drop.tags, vec![tag.clone()]);
+                if disposal == DropDisposal::DeleteUnread {
+                    assert_ne!(drop.drop.status, DropStatus::Unread);
                 }
-                assign.push(" tag_ids = ");
-                assign.push_bind_unseparated(tag_ids);
-                do_assign = true;
 
-                tags
-            } else {
-                find_tags(&mut *tx, &user, &hydrant.tag_ids).await?
-            };
+                // Clean up for the next round.
+                sqlx::query!("delete from drop_tags where drop_id = $1", drop.drop.id)
+                    .execute(&mut tx)
+                    .await
+                    .unwrap();
+                sqlx::query!("delete from drops where id = $1", drop.drop.id)
+                    .execute(&mut tx)
+                    .await
+                    .unwrap();
+            }
+
+            let hydrants = list_hydrants(&mut tx, &user).await.unwrap();
+            assert_eq!(hydrants, vec![]);
+        }
+    }
+
+    #[tokio::test]
+    async fn list_hydrants_empty() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let found = list_hydrants(&mut tx, &user).await.unwrap();
+        assert_eq!(found, vec![]);
+    }
+
+    #[tokio::test]
+    async fn list_hydrants_custom() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+
+        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+
+        let painted = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Painted".to_string(),
+                url: "https://example.com/painted".to_string(),
+                tags: Some(vec![
+                    TagSelector::Find { id: red.id },
+                    TagSelector::Find { id: blue.id },
+                ]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let only_blue = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Only Blue".to_string(),
+                url: "https://example.com/blue".to_string(),
+                tags: Some(vec![TagSelector::Find { id: blue.id }]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let found = list_hydrants(&mut tx, &user).await.unwrap();
+
+        let expected = vec![only_blue, painted];
+        assert_eq!(found, expected);
+    }
+
+    #[tokio::test]
+    async fn stale_hydrants_list() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let url = lorem_rss().unwrap().join("feed").unwrap();
+
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
+
+        let found = stale_hydrants(&mut tx, now).await.unwrap();
+        assert_eq!(found, vec![]);
+
+        // Leave this one unfetched.
+        let new = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "New".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let stale = {
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: "Stale".to_string(),
+                    url: url.to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            // Fetched longer ago than its interval, so it's due again.
+            Hydrant::fetch(
+                &mut tx,
+                &client,
+                hydrant.hydrant.id,
+                now - chrono::Duration::hours(2),
+            )
+            .await
+            .unwrap();
+
+            find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap()
+        };
+
+        let _fresh = {
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: "Fresh".to_string(),
+                    url: url.to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            Hydrant::fetch(
+                &mut tx,
+                &client,
+                hydrant.hydrant.id,
+                // Fetched within its interval, so it isn't due yet.
+                now - chrono::Duration::minutes(1),
+            )
+            .await
+            .unwrap();
+
+            find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap()
+        };
+
+        let _inactive = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Inactive".to_string(),
+                url: url.to_string(),
+                active: false,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-            query.push(" where id = ");
-            query.push_bind(hydrant.id);
-            query.push(" and user_id = ");
-            query.push_bind(user.id);
-            query.push(" returning *");
+        let found = stale_hydrants(&mut tx, now).await.unwrap();
 
-            if do_assign {
-                let hydrant: models::Hydrant = query.build_query_as().fetch_one(&mut *tx).await?;
-                Ok(Hydrant { hydrant, tags })
-            } else {
-                find_hydrant(&mut *tx, &user, hydrant.id).await
-            }
-        })
-    })
-    .await
-}
+        let expected = vec![new, stale];
+        assert_eq!(found, expected);
+    }
 
-pub async fn delete_hydrant(
-    conn: impl PgExecutor<'_>,
-    user: &models::User,
-    hydrant: models::Hydrant,
-) -> sqlx::Result<models::Hydrant> {
-    sqlx::query_as!(
-        models::Hydrant,
-        "
-        delete from hydrants
-        where id = $1
-        and user_id = $2
-        returning *
-        ",
-        hydrant.id,
-        user.id,
-    )
-    .fetch_one(conn)
-    .await
-}
+    #[tokio::test]
+    async fn fetch_skips_inactive_hydrant() {
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
 
-#[cfg(test)]
-mod tests {
-    use chrono::SubsecRound;
-    use sqlx::{Connection, PgConnection};
+        let user = test_user(&mut tx).await.unwrap();
 
-    use super::*;
-    use crate::auth;
+        let url = lorem_rss().unwrap().join("feed").unwrap();
 
-    // TODO: Make this a test transaction and roll it back on pass.
-    async fn test_conn() -> sqlx::Result<PgConnection> {
-        let url = std::env::var("TEST_DATABASE_URL").unwrap();
-        PgConnection::connect(&url).await
-    }
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
-    async fn test_user(conn: &mut PgConnection) -> sqlx::Result<models::User> {
-        let stytch_user_id: String = uuid::Uuid::new_v4().to_string();
-        auth::create_user(conn, stytch_user_id).await
-    }
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Inactive".to_string(),
+                url: url.to_string(),
+                active: false,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-    fn lorem_rss() -> anyhow::Result<url::Url> {
-        Ok(std::env::var("LOREM_RSS_URL")?.parse()?)
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+            .await
+            .unwrap();
+
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+            .await
+            .unwrap();
+
+        assert_eq!(found.hydrant.fetched_at, None);
     }
 
     #[tokio::test]
-    async fn minimal_drop() {
+    async fn fetch_empty_rss() {
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let created = create_drop(
+        let mut url = lorem_rss().unwrap();
+        url = url.join("feed").unwrap();
+        url.set_query(Some("length=0"));
+
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
             &mut tx,
             &user,
-            None,
-            "https://example.com/lorem-ipsum".to_string(),
-            None,
-            None,
-            chrono::Utc::now(),
+            NewHydrant {
+                name: "Empty".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
 
-        let found = find_drop(&mut tx, &user, created.drop.id).await.unwrap();
-        assert_eq!(found, created);
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+            .await
+            .unwrap();
+
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+            .await
+            .unwrap();
+
+        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
+        // number of subsecond digits (6).
+        assert_eq!(
+            found.hydrant.fetched_at,
+            Some(now.naive_utc().trunc_subsecs(6))
+        );
     }
 
     #[tokio::test]
-    async fn maximal_drop() {
+    async fn fetch_small_rss() {
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let coffee = create_tag(&mut tx, &user, "Coffee", "#c0ffee")
+        let mut url = lorem_rss().unwrap();
+        url = url.join("feed").unwrap();
+        url.set_query(Some("length=10"));
+
+        let now = chrono::Utc::now();
+
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "10 items/minute".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
             .await
             .unwrap();
 
-        let created = create_drop(
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+            .await
+            .unwrap();
+
+        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
+        // number of subsecond digits (6).
+        assert_eq!(
+            found.hydrant.fetched_at,
+            Some(now.naive_utc().trunc_subsecs(6))
+        );
+
+        let drops = list_drops(
             &mut tx,
             &user,
-            Some("Lorem Ipsum".to_string()),
-            "https://example.com/lorem-ipsum".to_string(),
-            None,
-            Some(vec![
-                TagSelector::Find { id: coffee.id },
-                TagSelector::Create {
-                    name: "ABC".to_string(),
-                    color: models::Tag::DEFAULT_COLOR.to_string(),
-                },
-            ]),
-            chrono::Utc::now(),
+            DropFilters {
+                status: Some(DropStatus::Unread),
+                ..Default::default()
+            },
+            Some(100),
         )
         .await
         .unwrap();
 
-        let found = find_drop(&mut tx, &user, created.drop.id).await.unwrap();
-        assert_eq!(found, created);
-
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["ABC", "Coffee"]);
+        assert_eq!(drops.len(), 10);
     }
 
     #[tokio::test]
-    async fn update_drop_fields() {
+    async fn fetch_skips_seen_items() {
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let drop = create_drop(
+        // Pick a long interval (once per month) to make finding a new item less likely.
+        let mut url = lorem_rss().unwrap();
+        url = url.join("feed").unwrap();
+        url.set_query(Some("unit=month&length=5"));
+
+        let now = chrono::Utc::now();
+
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
             &mut tx,
             &user,
-            None,
-            "https://example.com/lorem-ipsum".to_string(),
-            None,
-            None,
-            chrono::Utc::now(),
+            NewHydrant {
+                name: "5 items/month".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
 
-        let fields = DropFields {
-            title: Some("Dolor Sit".to_string()),
-            url: Some("https://example.com/dolor-sit".to_string()),
-        };
-        let tags = None;
+        for _ in 0..2 {
+            let now = now + chrono::Duration::minutes(1);
 
-        let updated = update_drop(&mut tx, &user, &drop.drop, fields, tags)
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
+
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+
+            // DB timestamps have microsecond precision, so truncate the local timestamp to the same
+            // number of subsecond digits (6).
+            assert_eq!(
+                found.hydrant.fetched_at,
+                Some(now.naive_utc().trunc_subsecs(6))
+            );
+
+            let drops = list_drops(
+                &mut tx,
+                &user,
+                DropFilters {
+                    status: Some(DropStatus::Unread),
+                    ..Default::default()
+                },
+                Some(100),
+            )
             .await
             .unwrap();
 
-        let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
-        assert_eq!(found, updated);
+            assert_eq!(drops.len(), 5);
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_not_modified() {
+        use axum::{http::HeaderMap, response::IntoResponse, routing::get};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user = test_user(&mut tx).await.unwrap();
+
+        let not_modified = Arc::new(AtomicUsize::new(0));
+        let counter = not_modified.clone();
+
+        let router = axum::Router::new().route(
+            "/feed",
+            get(move |headers: HeaderMap| async move {
+                let etag = r#""v1""#;
+                if headers.get(http::header::IF_NONE_MATCH).map(|v| v == etag) == Some(true) {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    return http::StatusCode::NOT_MODIFIED.into_response();
+                }
+                ([(http::header::ETAG, etag)], UNDATED_RSS).into_response()
+            }),
+        );
+        let url = test_server(router).join("feed").unwrap();
+
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Cached".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        for i in 0..2 {
+            let now = now + chrono::Duration::minutes(i);
+
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
+
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+
+            assert_eq!(found.hydrant.etag, Some(r#""v1""#.to_string()));
+            assert_eq!(
+                found.hydrant.fetched_at,
+                Some(now.naive_utc().trunc_subsecs(6))
+            );
+
+            // The items are undated, so they would have been imported again if the second fetch
+            // hadn't been a 304.
+            let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+                .await
+                .unwrap();
+            assert_eq!(drops.len(), 2);
+        }
 
-        assert_eq!(found.drop.title, Some("Dolor Sit".to_string()));
-        assert_eq!(found.drop.url, "https://example.com/dolor-sit".to_string(),);
+        assert_eq!(not_modified.load(Ordering::SeqCst), 1);
     }
 
     #[tokio::test]
-    async fn update_drop_tags() {
+    async fn fetch_skips_seen_undated_items() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let coffee = create_tag(&mut tx, &user, "Coffee", "#c0ffee")
-            .await
-            .unwrap();
+        let router = axum::Router::new().route("/feed", get(|| async { UNDATED_RSS }));
+        let url = test_server(router).join("feed").unwrap();
 
-        let drop = create_drop(
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
             &mut tx,
             &user,
-            None,
-            "https://example.com/lorem-ipsum".to_string(),
-            None,
-            Some(vec![TagSelector::Find { id: coffee.id }]),
-            chrono::Utc::now(),
+            NewHydrant {
+                name: "Undated".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
 
-        let fields = Default::default();
-        let tags = Some(vec![TagSelector::Create {
-            name: "ABC".to_string(),
-            color: models::Tag::DEFAULT_COLOR.to_string(),
-        }]);
-
-        let updated = update_drop(&mut tx, &user, &drop.drop, fields, tags)
-            .await
-            .unwrap();
-
-        let found = find_drop(&mut tx, &user, drop.drop.id).await.unwrap();
+        for i in 0..2 {
+            let now = now + chrono::Duration::minutes(i);
 
-        assert_eq!(found.drop, updated.drop);
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
 
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["ABC"]);
+            let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+                .await
+                .unwrap();
+            assert_eq!(drops.len(), 2);
+        }
     }
 
     #[tokio::test]
-    async fn change_drop_status() {
+    async fn fetch_backdated_items() {
+        use axum::{extract::State, routing::get};
+        use std::sync::{Arc, Mutex};
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
@@ -1562,703 +5550,1437 @@ mod tests {
 
         let now = chrono::Utc::now();
 
-        let drop = create_drop(
+        let rss = |items: &[(&str, chrono::DateTime<chrono::Utc>)]| {
+            let items: String = items
+                .iter()
+                .map(|(guid, date)| {
+                    format!(
+                        "<item><guid>{guid}</guid><link>https://example.com/{guid}</link><pubDate>{}</pubDate></item>",
+                        date.to_rfc2822()
+                    )
+                })
+                .collect();
+
+            format!(
+                r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Dated</title><link>https://example.com</link><description>Dated</description>{items}</channel></rss>"#
+            )
+        };
+
+        let body = Arc::new(Mutex::new(rss(&[("a", now)])));
+
+        let router = axum::Router::new()
+            .route(
+                "/feed",
+                get(|State(body): State<Arc<Mutex<String>>>| async move {
+                    body.lock().unwrap().clone()
+                }),
+            )
+            .with_state(body.clone());
+        let url = test_server(router).join("feed").unwrap();
+
+        let client = http_client::Client::default();
+
+        let hydrant = create_hydrant(
             &mut tx,
             &user,
-            None,
-            "https://example.com/lorem-ipsum".to_string(),
-            None,
-            None,
-            now,
+            NewHydrant {
+                name: "Backdated".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
 
-        let drop_id = drop.drop.id;
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+            .await
+            .unwrap();
 
-        let moved = move_drop(
-            &mut tx,
-            drop,
-            DropStatus::Read,
-            now + chrono::Duration::minutes(5),
-        )
-        .await
-        .unwrap();
+        // A new item shows up claiming to be a year old, and the first item gets bumped into the
+        // future. Only the new one is actually new.
+        *body.lock().unwrap() = rss(&[
+            ("a", now + chrono::Duration::days(1)),
+            ("b", now - chrono::Duration::days(365)),
+        ]);
 
-        let found = find_drop(&mut tx, &user, drop_id).await.unwrap();
-        assert_eq!(found, moved);
+        for i in 1..3 {
+            let now = now + chrono::Duration::minutes(i);
 
-        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
-        // number of subsecond digits (6).
-        assert_eq!(
-            found.drop.moved_at,
-            (now + chrono::Duration::minutes(5))
-                .naive_utc()
-                .trunc_subsecs(6)
-        );
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
+
+            let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+                .await
+                .unwrap();
+
+            let mut urls: Vec<String> = drops.into_iter().map(|d| d.drop.url).collect();
+            urls.sort();
+            assert_eq!(
+                urls,
+                vec![
+                    "https://example.com/a".to_string(),
+                    "https://example.com/b".to_string(),
+                ]
+            );
+        }
     }
 
     #[tokio::test]
-    async fn list_drops_by_status() {
+    async fn fetch_limits_ingestion() {
+        use axum::{extract::State, routing::get};
+        use std::sync::{Arc, Mutex};
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
+
         let now = chrono::Utc::now();
 
-        let mut unread = Vec::new();
-        let mut read = Vec::new();
-        for i in 0..10 {
-            let mut drop = create_drop(
-                &mut tx,
-                &user,
-                None,
-                format!("https://example.com/filtering/{}", i),
-                None,
-                None,
-                now + chrono::Duration::seconds(i.try_into().unwrap()),
+        let rss = |items: &[(&str, chrono::DateTime<chrono::Utc>)]| {
+            let items: String = items
+                .iter()
+                .map(|(guid, date)| {
+                    format!(
+                        "<item><guid>{guid}</guid><link>https://example.com/{guid}</link><pubDate>{}</pubDate></item>",
+                        date.to_rfc2822()
+                    )
+                })
+                .collect();
+
+            format!(
+                r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Busy</title><link>https://example.com</link><description>Busy</description>{items}</channel></rss>"#
             )
-            .await
-            .unwrap();
+        };
 
-            if i % 2 == 0 {
-                drop = move_drop(&mut tx, drop, DropStatus::Read, now)
-                    .await
-                    .unwrap();
-                read.push(drop);
-            } else {
-                unread.push(drop);
-            }
-        }
+        let day = chrono::Duration::days(1);
+        let backlog = [("c", now - day * 3), ("a", now - day), ("b", now - day * 2)];
+        let body = Arc::new(Mutex::new(rss(&backlog)));
 
-        let found_unread = list_drops(
+        let router = axum::Router::new()
+            .route(
+                "/feed",
+                get(|State(body): State<Arc<Mutex<String>>>| async move {
+                    body.lock().unwrap().clone()
+                }),
+            )
+            .with_state(body.clone());
+        let url = test_server(router).join("feed").unwrap();
+
+        let client = http_client::Client::default();
+
+        let policy = IngestionPolicy {
+            first_fetch_limit: Some(2),
+            max_drops_per_fetch: Some(1),
+            backfill_read: true,
+        };
+        let hydrant = create_hydrant(
             &mut tx,
             &user,
-            DropFilters {
-                status: Some(DropStatus::Unread),
+            NewHydrant {
+                name: "Busy".to_string(),
+                url: url.to_string(),
+                policy,
                 ..Default::default()
             },
-            None,
         )
         .await
         .unwrap();
-        assert_eq!(found_unread, unread);
-    }
 
-    #[tokio::test]
-    async fn list_drops_by_tags() {
-        let mut conn = test_conn().await.unwrap();
-        let mut tx = conn.begin().await.unwrap();
+        let drop_urls = |drops: Vec<Drop>| -> Vec<String> {
+            let mut urls: Vec<String> = drops.into_iter().map(|d| d.drop.url).collect();
+            urls.sort();
+            urls
+        };
 
-        let user = test_user(&mut tx).await.unwrap();
-        let now = chrono::Utc::now();
+        // The backfill only takes the newest items, and they're already read.
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+            .await
+            .unwrap();
 
-        // Set up state that looks like this:
-        //
-        // D0: {T0}
-        // D1: {T0, T1}
-        // D2: {T0, T1, T2}
+        let filters = DropFilters {
+            status: Some(DropStatus::Read),
+            ..Default::default()
+        };
+        let read = list_drops(&mut tx, &user, filters, Some(100))
+            .await
+            .unwrap();
+        assert_eq!(
+            drop_urls(read),
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
 
-        let mut tags = Vec::new();
-        for i in 0..3 {
-            let tag = create_tag(&mut tx, &user, &i.to_string(), models::Tag::DEFAULT_COLOR)
+        // After that, new items arrive unread, newest first, one fetch at a time.
+        let mut items = backlog.to_vec();
+        items.push(("d", now + chrono::Duration::hours(1)));
+        items.push(("e", now + chrono::Duration::hours(2)));
+        *body.lock().unwrap() = rss(&items);
+
+        let unread = DropFilters {
+            status: Some(DropStatus::Unread),
+            ..Default::default()
+        };
+        let mut expected: Vec<&str> = Vec::new();
+        for (i, url) in ["https://example.com/e", "https://example.com/d"]
+            .iter()
+            .enumerate()
+        {
+            let now = now + chrono::Duration::minutes(i as i64 + 1);
+
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
                 .await
                 .unwrap();
 
-            tags.push(tag);
+            expected.push(url);
+            expected.sort();
+            let drops = list_drops(&mut tx, &user, unread.clone(), Some(100))
+                .await
+                .unwrap();
+            assert_eq!(drop_urls(drops), expected);
         }
 
-        let mut drops = Vec::new();
-        for i in 0..3 {
-            let sel = tags[0..i + 1]
-                .iter()
-                .cloned()
-                .map(|tag| TagSelector::Find { id: tag.id })
-                .collect();
-
-            let drop = create_drop(
-                &mut tx,
-                &user,
-                None,
-                format!("https://example.com/filtering/{}", i),
-                None,
-                Some(sel),
-                now + chrono::Duration::seconds(i.try_into().unwrap()),
-            )
+        // The item that didn't make the backfill is never coming.
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
             .await
             .unwrap();
+        assert_eq!(drops.len(), 4);
+    }
 
-            assert_eq!(drop.tags.len(), i + 1);
+    #[tokio::test]
+    async fn fetch_keeps_deferred_items_past_not_modified() {
+        use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::get};
+        use std::sync::{Arc, Mutex};
 
-            drops.push(drop);
-        }
+        let mut conn = test_conn().await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
 
-        // Each tag selects suffix slices from the drops:
-        //
-        // T0: {D0, D1, D2}
-        // T1: {    D1, D2}
-        // T2: {        D2}
-        for i in 0..3 {
-            let found = list_drops(
-                &mut tx,
-                &user,
-                DropFilters {
-                    tags: Some(vec![tags[i].clone()]),
-                    ..Default::default()
-                },
-                Some(100),
-            )
-            .await
-            .unwrap();
+        let user = test_user(&mut tx).await.unwrap();
 
-            assert_eq!(found.len(), 3 - i);
+        let now = chrono::Utc::now();
 
-            let expected = &drops[i..];
-            assert_eq!(found, expected);
-        }
-    }
+        let rss = |items: &[(&str, chrono::DateTime<chrono::Utc>)]| {
+            let items: String = items
+                .iter()
+                .map(|(guid, date)| {
+                    format!(
+                        "<item><guid>{guid}</guid><link>https://example.com/{guid}</link><pubDate>{}</pubDate></item>",
+                        date.to_rfc2822()
+                    )
+                })
+                .collect();
 
-    #[tokio::test]
-    async fn update_tag_fields() {
-        let mut conn = test_conn().await.unwrap();
-        let mut conn = conn.begin().await.unwrap();
+            format!(
+                r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Busy</title><link>https://example.com</link><description>Busy</description>{items}</channel></rss>"#
+            )
+        };
 
-        let user = test_user(&mut conn).await.unwrap();
+        // The server answers with a 304 whenever it's sent the ETag of the current version.
+        let day = chrono::Duration::days(1);
+        let version = Arc::new(Mutex::new((r#""v1""#, rss(&[("a", now - day)]))));
+
+        let router = axum::Router::new()
+            .route(
+                "/feed",
+                get(
+                    |State(version): State<Arc<Mutex<(&'static str, String)>>>,
+                     headers: HeaderMap| async move {
+                        let (etag, body) = version.lock().unwrap().clone();
+                        if headers.get(http::header::IF_NONE_MATCH).map(|v| v == etag) == Some(true)
+                        {
+                            return http::StatusCode::NOT_MODIFIED.into_response();
+                        }
+                        ([(http::header::ETAG, etag)], body).into_response()
+                    },
+                ),
+            )
+            .with_state(version.clone());
+        let url = test_server(router).join("feed").unwrap();
 
-        let tag = create_tag(&mut conn, &user, "Work", "#0000ff")
+        let client = http_client::Client::default();
+
+        let policy = IngestionPolicy {
+            first_fetch_limit: None,
+            max_drops_per_fetch: Some(1),
+            backfill_read: false,
+        };
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Busy".to_string(),
+                url: url.to_string(),
+                policy,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
             .await
             .unwrap();
 
-        let tag_id = tag.id;
+        let hour = chrono::Duration::hours(1);
+        *version.lock().unwrap() = (
+            r#""v2""#,
+            rss(&[("a", now - day), ("b", now + hour), ("c", now + hour * 2)]),
+        );
 
-        let fields = TagFields {
-            name: Some("Play".to_string()),
-            color: Some("#00ff00".to_string()),
-        };
+        // Only one of the new items fits in each fetch. Until the other one has been added, the
+        // ETag isn't kept, so the next fetch can't get a 304 that would leave it behind.
+        let cases = [
+            (1, 2, None),
+            (2, 3, Some(r#""v2""#)),
+            (3, 3, Some(r#""v2""#)),
+        ];
 
-        let updated = update_tag(&mut conn, &user, tag, fields).await.unwrap();
+        for (i, expected_drops, expected_etag) in cases {
+            let now = now + chrono::Duration::minutes(i);
+
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
+
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+            assert_eq!(found.hydrant.etag.as_deref(), expected_etag);
 
-        let found = find_tag(&mut conn, &user, tag_id).await.unwrap();
-        assert_eq!(found, updated);
+            let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+                .await
+                .unwrap();
+            assert_eq!(drops.len(), expected_drops);
+        }
+    }
 
-        assert_eq!(found.name, "Play".to_string());
-        assert_eq!(found.color, "#00ff00".to_string());
+    #[test]
+    fn fetch_backoff() {
+        assert_eq!(backoff_minutes(10, 1), 20);
+        assert_eq!(backoff_minutes(10, 2), 40);
+        assert_eq!(backoff_minutes(10, 3), 80);
+        assert_eq!(backoff_minutes(60, 5), MAX_FETCH_BACKOFF_MINUTES);
+        assert_eq!(backoff_minutes(60, 1000), MAX_FETCH_BACKOFF_MINUTES);
+
+        // Failing never makes a hydrant get fetched more often than usual.
+        let weekly = 7 * 24 * 60;
+        assert_eq!(backoff_minutes(weekly, 1), i64::from(weekly));
+        assert_eq!(backoff_minutes(weekly, 5), i64::from(weekly));
     }
 
     #[tokio::test]
-    async fn empty_stream() {
+    async fn fetch_failures_back_off() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let res = create_stream(&mut tx, &user, "Empty", &[]).await;
-        let err = res.unwrap_err();
-        assert!(matches!(err, Error::Stream(StreamError::NoTags)));
-    }
+        let router = axum::Router::new().route(
+            "/feed",
+            get(|| async { http::StatusCode::INTERNAL_SERVER_ERROR }),
+        );
+        let url = test_server(router).join("feed").unwrap();
 
-    #[tokio::test]
-    async fn stream_with_tags() {
-        let mut conn = test_conn().await.unwrap();
-        let mut tx = conn.begin().await.unwrap();
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
-        let user = test_user(&mut tx).await.unwrap();
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Broken".to_string(),
+                url: url.to_string(),
+                fetch_interval_minutes: 10,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+        for i in 1..=MAX_FETCH_FAILURES {
+            let res = Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now).await;
+            assert!(res.is_err());
 
-        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+
+            assert_eq!(found.hydrant.failure_count, i);
+            assert!(found.hydrant.last_error.as_ref().unwrap().contains("500"));
+            assert_eq!(found.hydrant.fetched_at, None);
+
+            let backoff = chrono::Duration::minutes(backoff_minutes(10, i));
+            assert_eq!(
+                found.hydrant.next_attempt_at,
+                Some((now + backoff).naive_utc().trunc_subsecs(6))
+            );
+
+            // Backing off means it isn't stale again until the next attempt.
+            let stale = stale_hydrants(&mut tx, now).await.unwrap();
+            assert_eq!(stale, vec![]);
+
+            // And it gives up after too many failures.
+            assert_eq!(found.hydrant.active, i < MAX_FETCH_FAILURES);
+            if i == MAX_FETCH_FAILURES {
+                assert_eq!(found.health(), HydrantHealth::Dead);
+            }
+        }
 
-        let created = create_stream(&mut tx, &user, "Colors", &vec![red, blue])
+        // Turning it back on starts over.
+        let fields = HydrantFields {
+            active: Some(true),
+            ..Default::default()
+        };
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
             .await
             .unwrap();
-
-        let found = find_stream(&mut tx, &user, created.stream.id)
+        let updated = update_hydrant(&mut tx, &user, &found.hydrant, fields)
             .await
             .unwrap();
-        assert_eq!(created, found);
 
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["Blue", "Red"]);
+        assert!(updated.hydrant.active);
+        assert_eq!(updated.hydrant.failure_count, 0);
+        assert_eq!(updated.hydrant.last_error, None);
+        assert_eq!(updated.hydrant.next_attempt_at, None);
+        assert_eq!(updated.hydrant.deactivated_for, None);
+        assert_eq!(updated.health(), HydrantHealth::Pending);
     }
 
     #[tokio::test]
-    async fn change_stream_fields() {
+    async fn fetch_limits_body_size() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+        let router = axum::Router::new().route("/feed", get(|| async { UNDATED_RSS }));
+        let url = test_server(router).join("feed").unwrap();
 
-        let stream = create_stream(&mut tx, &user, "Oops!", &[red])
-            .await
-            .unwrap();
+        let now = chrono::Utc::now();
+        let config = http_client::Config {
+            max_body_bytes: 100,
+            ..Default::default()
+        };
+        let contact_url = url::Url::parse("https://example.com/").unwrap();
+        let client = http_client::Client::new(&config, &contact_url).unwrap();
 
-        let green = create_tag(&mut tx, &user, "Green", "#00ff00")
-            .await
-            .unwrap();
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Too big".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        let fields = StreamFields {
-            name: Some("Yay!".to_string()),
-            tag_ids: Some(vec![green.id]),
-        };
+        let res = Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now).await;
+        assert!(res.is_err());
 
-        let updated = update_stream(&mut tx, &user, &stream.stream, fields)
+        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
             .await
             .unwrap();
+        assert_eq!(found.hydrant.failure_count, 1);
+        assert_eq!(
+            found.hydrant.last_error.as_deref(),
+            Some("response body is larger than 100 bytes")
+        );
 
-        let found = find_stream(&mut tx, &user, stream.stream.id).await.unwrap();
-        assert_eq!(updated, found);
-
-        assert_eq!(found.stream.name, "Yay!".to_string());
-
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["Green"]);
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+            .await
+            .unwrap();
+        assert!(drops.is_empty());
     }
 
     #[tokio::test]
-    async fn list_streams_default() {
+    async fn fetch_identifies_itself() {
+        use axum::routing::get;
+        use std::sync::{Arc, Mutex};
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let found = list_streams(&mut tx, &user).await.unwrap();
+        let user_agent = Arc::new(Mutex::new(None));
+        let router = axum::Router::new().route(
+            "/feed",
+            get({
+                let user_agent = user_agent.clone();
+                move |headers: http::HeaderMap| async move {
+                    *user_agent.lock().unwrap() = headers
+                        .get(http::header::USER_AGENT)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string);
+                    UNDATED_RSS
+                }
+            }),
+        );
+        let url = test_server(router).join("feed").unwrap();
 
-        let expected = vec![
-            Stream::Status(StatusStream::new(DropStatus::Unread)),
-            Stream::Status(StatusStream::new(DropStatus::Read)),
-            Stream::Status(StatusStream::new(DropStatus::Saved)),
-        ];
+        let now = chrono::Utc::now();
+        let contact_url = url::Url::parse("https://metagram.example/").unwrap();
+        let client = http_client::Client::new(&Default::default(), &contact_url).unwrap();
 
-        assert_eq!(found, expected);
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Polite".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            user_agent.lock().unwrap().as_deref(),
+            Some("Metagram/Firehose (+https://metagram.example/)")
+        );
     }
 
     #[tokio::test]
-    async fn list_streams_custom() {
+    async fn fetch_follows_permanent_redirects() {
+        use axum::response::Redirect;
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+        let router = axum::Router::new()
+            .route("/feed", get(|| async { UNDATED_RSS }))
+            .route("/moved", get(|| async { Redirect::permanent("/feed") }))
+            .route("/temporary", get(|| async { Redirect::temporary("/feed") }))
+            .route("/chain", get(|| async { Redirect::temporary("/moved") }))
+            .route(
+                "/old",
+                get(|| async {
+                    (
+                        http::StatusCode::MOVED_PERMANENTLY,
+                        [(http::header::LOCATION, "/moved")],
+                    )
+                }),
+            );
+        let base = test_server(router);
 
-        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
+
+        // Only a chain of redirects that are all permanent means the feed has moved.
+        let cases = [
+            ("old", "feed", 1),
+            ("moved", "feed", 1),
+            ("temporary", "temporary", 0),
+            ("chain", "chain", 0),
+        ];
 
-        let colors = create_stream(&mut tx, &user, "Colors", &vec![red, blue.clone()])
+        for (path, expected_path, expected_events) in cases {
+            let url = base.join(path).unwrap();
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: path.to_string(),
+                    url: url.to_string(),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap();
 
-        let only_blue = create_stream(&mut tx, &user, "Only Blue", &[blue.clone()])
-            .await
-            .unwrap();
+            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+                .await
+                .unwrap();
 
-        let found = list_streams(&mut tx, &user).await.unwrap();
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+            assert_eq!(
+                found.hydrant.url,
+                base.join(expected_path).unwrap().as_str()
+            );
+            assert_eq!(found.health(), HydrantHealth::Healthy);
 
-        let expected = vec![
-            Stream::Status(StatusStream::new(DropStatus::Unread)),
-            Stream::Status(StatusStream::new(DropStatus::Read)),
-            Stream::Status(StatusStream::new(DropStatus::Saved)),
-            Stream::Custom(CustomStream { ..colors }),
-            Stream::Custom(CustomStream { ..only_blue }),
-        ];
-        assert_eq!(found, expected);
+            let events = list_hydrant_events(&mut tx, &found.hydrant, 10)
+                .await
+                .unwrap();
+            assert_eq!(events.len(), expected_events, "{}", path);
+            if let Some(event) = events.first() {
+                assert_eq!(event.kind, models::HydrantEventKind::Moved);
+                assert_eq!(event.old_url.as_deref(), Some(url.as_str()));
+                assert_eq!(event.new_url, Some(found.hydrant.url));
+            }
+        }
     }
 
     #[tokio::test]
-    async fn simple_hydrant() {
-        let mut conn = test_conn().await.unwrap();
-        let mut tx = conn.begin().await.unwrap();
+    async fn redirects_drop_validators_across_origins() {
+        use axum::response::Redirect;
+        use axum::routing::get;
+
+        // Both servers claim to have a fresh copy of anything that has an ETag.
+        fn router() -> axum::Router {
+            axum::Router::new().route(
+                "/feed",
+                get(|headers: http::HeaderMap| async move {
+                    if headers.contains_key(http::header::IF_NONE_MATCH) {
+                        http::StatusCode::NOT_MODIFIED
+                    } else {
+                        http::StatusCode::OK
+                    }
+                }),
+            )
+        }
 
-        let user = test_user(&mut tx).await.unwrap();
+        let other = test_server(router());
+        let other_feed = other.join("feed").unwrap().to_string();
+        let base = test_server(
+            router()
+                .route("/here", get(|| async { Redirect::temporary("/feed") }))
+                .route(
+                    "/there",
+                    get(move || async move { Redirect::temporary(&other_feed) }),
+                ),
+        );
 
-        let created = create_hydrant(
-            &mut tx,
-            &user,
-            "Simple",
-            "https://example.com/simple",
-            true,
-            None,
-        )
-        .await
-        .unwrap();
+        let client = http_client::Client::default();
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::IF_NONE_MATCH, "\"v1\"".parse().unwrap());
 
-        let found = find_hydrant(&mut tx, &user, created.hydrant.id)
-            .await
-            .unwrap();
-        assert_eq!(created, found);
+        let cases = [
+            ("here", http::StatusCode::NOT_MODIFIED),
+            ("there", http::StatusCode::OK),
+        ];
 
-        assert_eq!(found.hydrant.name, "Simple".to_string());
-        assert_eq!(found.hydrant.url, "https://example.com/simple".to_string());
-        assert!(found.hydrant.active);
-        assert_eq!(found.tags, vec![]);
+        for (path, expected) in cases {
+            let url = base.join(path).unwrap();
+            let response = client.get(url.as_str(), headers.clone()).await.unwrap();
+            assert_eq!(response.inner.status(), expected, "{}", path);
+        }
     }
 
     #[tokio::test]
-    async fn tagged_hydrant() {
+    async fn fetch_deactivates_gone_feeds() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+        let router = axum::Router::new()
+            .route("/gone", get(|| async { http::StatusCode::GONE }))
+            .route("/missing", get(|| async { http::StatusCode::NOT_FOUND }));
+        let base = test_server(router);
 
-        let created = create_hydrant(
-            &mut tx,
-            &user,
-            "Painted",
-            "https://example.com/painted",
-            true,
-            Some(vec![
-                TagSelector::Find { id: red.id },
-                TagSelector::Create {
-                    name: "Blue".to_string(),
-                    color: "#0000ff".to_string(),
-                },
-            ]),
-        )
-        .await
-        .unwrap();
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
-        let found = find_hydrant(&mut tx, &user, created.hydrant.id)
+        for path in ["gone", "missing"] {
+            let url = base.join(path).unwrap();
+            let hydrant = create_hydrant(
+                &mut tx,
+                &user,
+                NewHydrant {
+                    name: path.to_string(),
+                    url: url.to_string(),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap();
-        assert_eq!(created, found);
 
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["Blue", "Red"]);
+            for i in 0..MAX_GONE_FETCHES {
+                let now = now + chrono::Duration::hours(i);
+
+                let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                    .await
+                    .unwrap();
+                assert!(found.hydrant.active);
+
+                let res = Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now).await;
+                assert!(res.is_err());
+            }
+
+            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+                .await
+                .unwrap();
+            assert!(!found.hydrant.active);
+            assert_eq!(found.health(), HydrantHealth::Gone);
+
+            let events = list_hydrant_events(&mut tx, &found.hydrant, 10)
+                .await
+                .unwrap();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].kind, models::HydrantEventKind::Gone);
+            assert_eq!(events[0].old_url.as_deref(), Some(url.as_str()));
+        }
     }
 
     #[tokio::test]
-    async fn change_hydrant_fields() {
+    async fn websub_subscription() {
+        use axum::extract::Form;
+        use axum::routing::{get, post};
+        use std::sync::{Arc, Mutex};
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let black = create_tag(&mut tx, &user, "Black", "#ff0000")
-            .await
-            .unwrap();
+        // A stand-in hub that accepts every request and remembers it.
+        let requests: Arc<Mutex<Vec<HashMap<String, String>>>> = Default::default();
+        let hub_router = axum::Router::new().route(
+            "/hub",
+            post({
+                let requests = requests.clone();
+                move |Form(form): Form<HashMap<String, String>>| async move {
+                    requests.lock().unwrap().push(form);
+                    http::StatusCode::ACCEPTED
+                }
+            }),
+        );
+        let hub_url = test_server(hub_router).join("hub").unwrap();
+
+        let rss = |items: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+                <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+                  <channel>
+                    <title>Pushy</title>
+                    <atom:link rel="hub" href="{hub_url}"/>
+                    <atom:link rel="self" href="https://example.com/topic"/>
+                    {items}
+                  </channel>
+                </rss>
+                "#
+            )
+        };
+
+        let polled = rss("<item><guid>1</guid><link>https://example.com/1</link></item>");
+        let router = axum::Router::new().route("/feed", get(move || async move { polled }));
+        let url = test_server(router).join("feed").unwrap();
+
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
         let hydrant = create_hydrant(
             &mut tx,
             &user,
-            "The Rolling Stones",
-            "https://example.com/painted",
-            true,
-            Some(vec![TagSelector::Find { id: black.id }]),
+            NewHydrant {
+                name: "Pushy".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
+        let id = hydrant.hydrant.id;
 
-        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+        Hydrant::fetch(&mut tx, &client, id, now).await.unwrap();
 
-        let fields = HydrantFields {
-            name: Some("Eiffel 65".to_string()),
-            url: Some("https://example.com/blue".to_string()),
-            active: Some(false),
-            tags: Some(vec![TagSelector::Find { id: blue.id }]),
-        };
+        let subscription = find_subscription(&mut tx, id).await.unwrap().unwrap();
+        assert_eq!(subscription.hub_url, hub_url.to_string());
+        assert_eq!(subscription.topic_url, "https://example.com/topic");
 
-        let updated = update_hydrant(&mut tx, &user, &hydrant.hydrant, fields)
+        let due = due_subscriptions(&mut tx, now).await.unwrap();
+        assert_eq!(due, vec![subscription.clone()]);
+
+        let callback = url::Url::parse("https://metagram.example/callback").unwrap();
+        request_subscription(&mut tx, &client, &subscription, &callback, now)
             .await
             .unwrap();
 
-        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+        let form = requests.lock().unwrap().pop().unwrap();
+        assert_eq!(form["hub.mode"], "subscribe");
+        assert_eq!(form["hub.topic"], "https://example.com/topic");
+        assert_eq!(form["hub.callback"], "https://metagram.example/callback");
+        assert_eq!(form["hub.secret"], subscription.secret);
+
+        // Unconfirmed requests get retried, but not right away.
+        let due = due_subscriptions(&mut tx, now).await.unwrap();
+        assert!(due.is_empty());
+        let later = now + chrono::Duration::hours(SUBSCRIPTION_RETRY_HOURS + 1);
+        let due = due_subscriptions(&mut tx, later).await.unwrap();
+        assert_eq!(due.len(), 1);
+
+        let mut verification = websub::Verification {
+            mode: websub::Mode::Subscribe,
+            topic: "https://example.com/other".to_string(),
+            challenge: Some("challenge".to_string()),
+            lease_seconds: Some(10 * 24 * 60 * 60),
+            reason: None,
+        };
+        let verified = verify_subscription(&mut tx, id, &verification, now)
             .await
             .unwrap();
-        assert_eq!(updated, found);
+        assert!(!verified);
+
+        // Leases that are too long to be real are refused instead of overflowing.
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("hub.mode", "subscribe")
+            .append_pair("hub.topic", &subscription.topic_url)
+            .append_pair("hub.lease_seconds", "9223372036854775807")
+            .finish();
+        let uri: http::Uri = format!("/callback?{}", query).parse().unwrap();
+        let axum::extract::Query(endless) =
+            axum::extract::Query::<websub::Verification>::try_from_uri(&uri).unwrap();
+        let verified = verify_subscription(&mut tx, id, &endless, now)
+            .await
+            .unwrap();
+        assert!(!verified);
 
-        assert_eq!(found.hydrant.name, "Eiffel 65".to_string());
-        assert_eq!(found.hydrant.url, "https://example.com/blue".to_string());
-        assert!(!found.hydrant.active);
+        verification.topic = subscription.topic_url.clone();
+        let verified = verify_subscription(&mut tx, id, &verification, now)
+            .await
+            .unwrap();
+        assert!(verified);
+
+        // Confirmed subscriptions are left alone until they're about to run out.
+        let due = due_subscriptions(&mut tx, later).await.unwrap();
+        assert!(due.is_empty());
+        let expiring = now + chrono::Duration::days(9);
+        let due = due_subscriptions(&mut tx, expiring).await.unwrap();
+        assert_eq!(due.len(), 1);
+
+        // Pushed content goes through the same path as polling, so items already seen are
+        // skipped.
+        let pushed = rss("
+            <item><guid>1</guid><link>https://example.com/1</link></item>
+            <item><guid>2</guid><link>https://example.com/2</link></item>
+        ");
+        let signature = websub::sign(&subscription.secret, pushed.as_bytes());
+
+        let outcome = Hydrant::receive(&mut tx, id, Some("sha256=00"), pushed.as_bytes(), now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, PushOutcome::Ignored);
 
-        let tag_names: Vec<&str> = found.tags.iter().map(|t| &t.name[..]).collect();
-        assert_eq!(tag_names, vec!["Blue"]);
-    }
+        let garbage = b"not a feed";
+        let garbage_signature = websub::sign(&subscription.secret, garbage);
+        let outcome = Hydrant::receive(&mut tx, id, Some(&garbage_signature), garbage, now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, PushOutcome::Ignored);
 
-    #[tokio::test]
-    async fn list_hydrants_empty() {
-        let mut conn = test_conn().await.unwrap();
-        let mut tx = conn.begin().await.unwrap();
+        let outcome = Hydrant::receive(&mut tx, id, Some(&signature), pushed.as_bytes(), now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, PushOutcome::Added(1));
+
+        let outcome = Hydrant::receive(&mut tx, id, Some(&signature), pushed.as_bytes(), now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, PushOutcome::Added(0));
 
-        let user = test_user(&mut tx).await.unwrap();
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
+            .await
+            .unwrap();
+        let mut urls: Vec<&str> = drops.iter().map(|d| d.drop.url.as_str()).collect();
+        urls.sort();
+        assert_eq!(urls, vec!["https://example.com/1", "https://example.com/2"]);
 
-        let found = list_hydrants(&mut tx, &user).await.unwrap();
-        assert_eq!(found, vec![]);
+        let outcome = Hydrant::receive(&mut tx, Uuid::new_v4(), Some(&signature), b"", now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, PushOutcome::NotSubscribed);
     }
 
     #[tokio::test]
-    async fn list_hydrants_custom() {
+    async fn fetch_records_history() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let red = create_tag(&mut tx, &user, "Red", "#ff0000").await.unwrap();
+        let router = axum::Router::new()
+            .route("/feed", get(|| async { UNDATED_RSS }))
+            .route("/gone", get(|| async { http::StatusCode::GONE }));
+        let base = test_server(router);
 
-        let blue = create_tag(&mut tx, &user, "Blue", "#0000ff").await.unwrap();
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
-        let painted = create_hydrant(
+        let working_url = base.join("feed").unwrap();
+        let working = create_hydrant(
             &mut tx,
             &user,
-            "Painted",
-            "https://example.com/painted",
-            true,
-            Some(vec![
-                TagSelector::Find { id: red.id },
-                TagSelector::Find { id: blue.id },
-            ]),
+            NewHydrant {
+                name: "Working".to_string(),
+                url: working_url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
+        assert_eq!(working.health(), HydrantHealth::Pending);
 
-        let only_blue = create_hydrant(
+        let broken_url = base.join("gone").unwrap();
+        let broken = create_hydrant(
             &mut tx,
             &user,
-            "Only Blue",
-            "https://example.com/blue",
-            true,
-            Some(vec![TagSelector::Find { id: blue.id }]),
+            NewHydrant {
+                name: "Broken".to_string(),
+                url: broken_url.to_string(),
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
 
-        let found = list_hydrants(&mut tx, &user).await.unwrap();
+        for i in 0..2 {
+            let now = now + chrono::Duration::minutes(i);
 
-        let expected = vec![only_blue, painted];
-        assert_eq!(found, expected);
+            Hydrant::fetch(&mut tx, &client, working.hydrant.id, now)
+                .await
+                .unwrap();
+            Hydrant::fetch(&mut tx, &client, broken.hydrant.id, now)
+                .await
+                .unwrap_err();
+        }
+
+        let found = find_hydrant(&mut tx, &user, working.hydrant.id)
+            .await
+            .unwrap();
+        assert_eq!(found.health(), HydrantHealth::Healthy);
+
+        let fetches = list_hydrant_fetches(&mut tx, &found.hydrant, 10)
+            .await
+            .unwrap();
+        let summary: Vec<_> = fetches
+            .iter()
+            .map(|f| {
+                (
+                    f.fetched_at,
+                    f.status,
+                    f.bytes,
+                    f.item_count,
+                    f.drop_count,
+                    f.error.clone(),
+                )
+            })
+            .collect();
+        let bytes = Some(UNDATED_RSS.len() as i64);
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    (now + chrono::Duration::minutes(1))
+                        .naive_utc()
+                        .trunc_subsecs(6),
+                    Some(200),
+                    bytes,
+                    Some(2),
+                    Some(0),
+                    None
+                ),
+                (
+                    now.naive_utc().trunc_subsecs(6),
+                    Some(200),
+                    bytes,
+                    Some(2),
+                    Some(2),
+                    None
+                ),
+            ]
+        );
+
+        let found = find_hydrant(&mut tx, &user, broken.hydrant.id)
+            .await
+            .unwrap();
+        assert_eq!(found.health(), HydrantHealth::Failing);
+
+        let fetches = list_hydrant_fetches(&mut tx, &found.hydrant, 10)
+            .await
+            .unwrap();
+        assert_eq!(fetches.len(), 2);
+        for fetch in fetches {
+            assert_eq!(fetch.status, Some(410));
+            assert_eq!(fetch.item_count, None);
+            assert_eq!(fetch.drop_count, None);
+            assert!(fetch.error.unwrap().contains("410"));
+        }
+
+        // Old history gets cleaned up.
+        let cleared = clear_hydrant_fetches(&mut tx, now + chrono::Duration::seconds(30))
+            .await
+            .unwrap();
+        assert_eq!(cleared, 2);
     }
 
     #[tokio::test]
-    async fn stale_hydrants_list() {
+    async fn preview_feeds() {
+        use axum::routing::get;
+
+        let router = axum::Router::new()
+            .route("/feed", get(|| async { UNDATED_RSS }))
+            .route("/page", get(|| async { "<html></html>" }))
+            .route("/blog", get(|| async { BLOG_HTML }))
+            .route("/blogs", get(|| async { BLOGS_HTML }))
+            .route("/gone", get(|| async { http::StatusCode::GONE }));
+        let base = test_server(router);
+
+        let client = http_client::Client::default();
+
+        let url = base.join("feed").unwrap();
+        let found = preview_feed(&client, url.as_ref()).await.unwrap();
+        assert_eq!(found.url, url.to_string());
+        assert_eq!(found.feed.title, Some("Undated".to_string()));
+        assert_eq!(found.feed.entries.len(), 2);
+
+        // A page with one feed link uses that feed.
+        let found = preview_feed(&client, base.join("blog").unwrap().as_ref())
+            .await
+            .unwrap();
+        assert_eq!(found.url, url.to_string());
+        assert_eq!(found.feed.entries.len(), 2);
+
+        // But one with several needs someone to pick.
+        let res = preview_feed(&client, base.join("blogs").unwrap().as_ref()).await;
+        match res {
+            Err(FeedError::Ambiguous(links)) => {
+                let urls: Vec<String> = links.into_iter().map(|l| l.url).collect();
+                assert_eq!(
+                    urls,
+                    vec![url.to_string(), base.join("atom").unwrap().to_string()]
+                );
+            }
+            _ => panic!("expected ambiguous feeds, got {:?}", res),
+        }
+
+        let res = preview_feed(&client, base.join("page").unwrap().as_ref()).await;
+        assert!(matches!(res, Err(FeedError::Parse(_))));
+
+        let res = preview_feed(&client, base.join("gone").unwrap().as_ref()).await;
+        assert!(matches!(res, Err(FeedError::Fetch(_))));
+    }
+
+    #[tokio::test]
+    async fn fetch_discovers_feed() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let url = lorem_rss().unwrap().join("feed").unwrap();
+        let router = axum::Router::new()
+            .route("/feed", get(|| async { UNDATED_RSS }))
+            .route("/blog", get(|| async { BLOG_HTML }))
+            .route("/blogs", get(|| async { BLOGS_HTML }));
+        let base = test_server(router);
 
         let now = chrono::Utc::now();
-        let client = reqwest::Client::new();
+        let client = http_client::Client::default();
 
-        let found = stale_hydrants(&mut tx, now).await.unwrap();
-        assert_eq!(found, vec![]);
+        let blog_url = base.join("blog").unwrap();
+        let blog = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Blog".to_string(),
+                url: blog_url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        // Leave this one unfetched.
-        let new = create_hydrant(&mut tx, &user, "New", url.as_ref(), true, None)
+        Hydrant::fetch(&mut tx, &client, blog.hydrant.id, now)
             .await
             .unwrap();
 
-        let stale = {
-            let hydrant = create_hydrant(&mut tx, &user, "Stale", url.as_ref(), true, None)
-                .await
-                .unwrap();
-            Hydrant::fetch(
-                &mut tx,
-                &client,
-                hydrant.hydrant.id,
-                now - chrono::Duration::minutes(1),
-            )
+        let found = find_hydrant(&mut tx, &user, blog.hydrant.id).await.unwrap();
+        assert_eq!(found.hydrant.url, base.join("feed").unwrap().to_string());
+
+        let events = list_hydrant_events(&mut tx, &found.hydrant, 10)
             .await
             .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, models::HydrantEventKind::Discovered);
+        assert_eq!(events[0].old_url, Some(blog_url.to_string()));
+        assert_eq!(events[0].new_url, Some(found.hydrant.url.clone()));
 
-            find_hydrant(&mut tx, &user, hydrant.hydrant.id)
-                .await
-                .unwrap()
-        };
-
-        let _fresh = {
-            let hydrant = create_hydrant(&mut tx, &user, "Fresh", url.as_ref(), true, None)
-                .await
-                .unwrap();
-            Hydrant::fetch(
-                &mut tx,
-                &client,
-                hydrant.hydrant.id,
-                // Avoid sub-second equality issues by ensuring that this one's fetched_at is
-                // strictly greater than now.
-                now + chrono::Duration::seconds(1),
-            )
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
             .await
             .unwrap();
+        assert_eq!(drops.len(), 2);
 
-            find_hydrant(&mut tx, &user, hydrant.hydrant.id)
-                .await
-                .unwrap()
-        };
+        let blogs_url = base.join("blogs").unwrap();
+        let blogs = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Blogs".to_string(),
+                url: blogs_url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = Hydrant::fetch(&mut tx, &client, blogs.hydrant.id, now)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FeedError>(),
+            Some(FeedError::Ambiguous(_))
+        ));
 
-        let _inactive = create_hydrant(&mut tx, &user, "Inactive", url.as_ref(), false, None)
+        let found = find_hydrant(&mut tx, &user, blogs.hydrant.id)
             .await
             .unwrap();
+        assert_eq!(found.hydrant.url, blogs_url.to_string());
+    }
 
-        let found = stale_hydrants(&mut tx, now).await.unwrap();
+    #[test]
+    fn filter_rules() {
+        let story = |title: &str, url: &str| Story {
+            key: url.to_string(),
+            title: Some(title.to_string()),
+            url: url.to_string(),
+            published_at: None,
+        };
 
-        let expected = vec![new, stale];
-        assert_eq!(found, expected);
+        let rule = FilterRule::parse("Rust").unwrap();
+        assert!(rule.matches(&story("Announcing RUST 2.0", "https://example.com/a")));
+        assert!(rule.matches(&story("Untitled", "https://example.com/rust/b")));
+        assert!(!rule.matches(&story("Announcing Go 2.0", "https://example.com/c")));
+
+        let rule = FilterRule::parse(r"/^\[ad\]/").unwrap();
+        assert!(rule.matches(&story("[ad] Buy things", "https://example.com/a")));
+        assert!(!rule.matches(&story("Not an [ad]", "https://example.com/b")));
+
+        let rule = FilterRule::parse("domain:Example.com").unwrap();
+        assert!(rule.matches(&story("A", "https://example.com/a")));
+        assert!(rule.matches(&story("B", "https://blog.example.com/b")));
+        assert!(!rule.matches(&story("C", "https://notexample.com/c")));
+
+        assert!(FilterRule::parse("/(unclosed/").is_err());
+
+        let filter = StoryFilter {
+            include: vec![],
+            exclude: vec![],
+        };
+        assert!(filter.accepts(&story("Anything", "https://example.com/")));
+
+        let filter = StoryFilter {
+            include: vec![
+                FilterRule::parse("rust").unwrap(),
+                FilterRule::parse("domain:example.org").unwrap(),
+            ],
+            exclude: vec![FilterRule::parse("sponsored").unwrap()],
+        };
+        assert!(filter.accepts(&story("Rust news", "https://example.com/a")));
+        assert!(filter.accepts(&story("Other news", "https://example.org/b")));
+        assert!(!filter.accepts(&story("Other news", "https://example.com/c")));
+        assert!(!filter.accepts(&story("Sponsored: Rust", "https://example.com/d")));
     }
 
     #[tokio::test]
-    async fn fetch_skips_inactive_hydrant() {
+    async fn fetch_filters_stories() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let url = lorem_rss().unwrap().join("feed").unwrap();
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0">
+              <channel>
+                <title>Mixed</title>
+                <item><title>Rust 2.0</title><link>https://example.com/rust</link></item>
+                <item><title>Sponsored: Rust jobs</title><link>https://example.com/jobs</link></item>
+                <item><title>Cooking</title><link>https://example.com/cooking</link></item>
+                <item><title>Elsewhere</title><link>https://rust.example.org/</link></item>
+              </channel>
+            </rss>
+        "#;
+
+        let router = axum::Router::new().route("/feed", get(move || async move { rss }));
+        let url = test_server(router).join("feed").unwrap();
 
         let now = chrono::Utc::now();
-        let client = reqwest::Client::new();
+        let client = http_client::Client::default();
 
-        let hydrant = create_hydrant(&mut tx, &user, "Inactive", url.as_ref(), false, None)
-            .await
-            .unwrap();
+        let rules = FilterRules {
+            include: vec!["rust".to_string()],
+            exclude: vec!["/^Sponsored/".to_string(), "domain:example.org".to_string()],
+        };
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Mixed".to_string(),
+                url: url.to_string(),
+                rules,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(hydrant.hydrant.include_rules, vec!["rust"]);
 
         Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
             .await
             .unwrap();
 
-        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
             .await
             .unwrap();
+        let urls: Vec<&str> = drops.iter().map(|d| d.drop.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://example.com/rust"]);
 
-        assert_eq!(found.hydrant.fetched_at, None);
+        let fetches = list_hydrant_fetches(&mut tx, &hydrant.hydrant, 10)
+            .await
+            .unwrap();
+        assert_eq!(fetches.len(), 1);
+        assert_eq!(fetches[0].item_count, Some(4));
+        assert_eq!(fetches[0].drop_count, Some(1));
+        assert_eq!(fetches[0].filtered_count, Some(3));
     }
 
     #[tokio::test]
-    async fn fetch_empty_rss() {
+    async fn fetch_drop_metadata_fills_gaps() {
+        use axum::response::Html;
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
+        let now = chrono::Utc::now();
 
-        let mut url = lorem_rss().unwrap();
-        url = url.join("feed").unwrap();
-        url.set_query(Some("length=0"));
+        let page = r#"<html>
+            <head>
+              <title>Lorem Ipsum</title>
+              <meta property="og:description" content="Dolor sit amet.">
+              <meta property="og:site_name" content="Example">
+              <meta property="og:image" content="/lorem.png">
+            </head>
+        </html>"#;
 
-        let now = chrono::Utc::now();
-        let client = reqwest::Client::new();
+        let router = axum::Router::new().route("/lorem", get(move || async move { Html(page) }));
+        let url = test_server(router).join("lorem").unwrap();
+        let client = http_client::Client::default();
 
-        let hydrant = create_hydrant(&mut tx, &user, "Empty", url.as_ref(), true, None)
+        let untitled = create_drop(&mut tx, &user, None, url.to_string(), None, None, now)
             .await
             .unwrap();
 
-        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
+        fetch_drop_metadata(&mut tx, &client, untitled.drop.id, now)
             .await
             .unwrap();
 
-        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+        let found = find_drop(&mut tx, &user, untitled.drop.id).await.unwrap();
+        assert_eq!(found.drop.title, Some("Lorem Ipsum".to_string()));
+        assert_eq!(found.drop.description, Some("Dolor sit amet.".to_string()));
+        assert_eq!(found.drop.site_name, Some("Example".to_string()));
+        assert_eq!(
+            found.drop.image_url,
+            Some(url.join("/lorem.png").unwrap().to_string())
+        );
+        assert_eq!(found.drop.canonical_link, None);
+        assert!(found.drop.metadata_fetched_at.is_some());
+
+        // Whatever's already there stays.
+        let fields = DropFields {
+            title: Some("My Title".to_string()),
+            ..Default::default()
+        };
+        update_drop(&mut tx, &user, &found.drop, fields, None)
             .await
             .unwrap();
 
-        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
-        // number of subsecond digits (6).
-        assert_eq!(
-            found.hydrant.fetched_at,
-            Some(now.naive_utc().trunc_subsecs(6))
-        );
+        fetch_drop_metadata(&mut tx, &client, untitled.drop.id, now)
+            .await
+            .unwrap();
+
+        let found = find_drop(&mut tx, &user, untitled.drop.id).await.unwrap();
+        assert_eq!(found.drop.title, Some("My Title".to_string()));
+
+        // The drop might be gone by the time this runs.
+        fetch_drop_metadata(&mut tx, &client, Uuid::new_v4(), now)
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
-    async fn fetch_small_rss() {
+    async fn fetch_reads_sitemaps() {
+        use axum::routing::get;
+
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        let mut url = lorem_rss().unwrap();
-        url = url.join("feed").unwrap();
-        url.set_query(Some("length=10"));
+        let sitemap = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://example.com/lorem</loc><lastmod>2022-10-01</lastmod></url>
+              <url><loc>https://example.com/ipsum</loc></url>
+            </urlset>
+        "#;
 
-        let now = chrono::Utc::now();
+        let router = axum::Router::new().route("/sitemap.xml", get(move || async move { sitemap }));
+        let url = test_server(router).join("sitemap.xml").unwrap();
 
-        let client = reqwest::Client::new();
+        let now = chrono::Utc::now();
+        let client = http_client::Client::default();
 
-        let hydrant = create_hydrant(&mut tx, &user, "10 items/minute", url.as_ref(), true, None)
+        let found = preview_hydrant(&client, HydrantSourceKind::Sitemap, url.as_ref())
             .await
             .unwrap();
+        assert_eq!(found.feed.entries.len(), 2);
+
+        let res = preview_hydrant(&client, HydrantSourceKind::Feed, url.as_ref()).await;
+        assert!(matches!(res, Err(FeedError::Parse(_))));
+
+        let hydrant = create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Pages".to_string(),
+                url: url.to_string(),
+                source_kind: HydrantSourceKind::Sitemap,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
         Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
             .await
             .unwrap();
 
-        let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
+        let drops = list_drops(&mut tx, &user, Default::default(), Some(100))
             .await
             .unwrap();
-
-        // DB timestamps have microsecond precision, so truncate the local timestamp to the same
-        // number of subsecond digits (6).
+        let mut urls: Vec<&str> = drops.iter().map(|d| d.drop.url.as_str()).collect();
+        urls.sort();
         assert_eq!(
-            found.hydrant.fetched_at,
-            Some(now.naive_utc().trunc_subsecs(6))
+            urls,
+            vec!["https://example.com/ipsum", "https://example.com/lorem"]
         );
 
-        let drops = list_drops(
-            &mut tx,
-            &user,
-            DropFilters {
-                status: Some(DropStatus::Unread),
-                ..Default::default()
-            },
-            Some(100),
-        )
-        .await
-        .unwrap();
+        // Pages already in the sitemap aren't new the next time around.
+        let later = now + chrono::Duration::hours(1);
+        Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, later)
+            .await
+            .unwrap();
 
-        assert_eq!(drops.len(), 10);
+        let fetches = list_hydrant_fetches(&mut tx, &hydrant.hydrant, 10)
+            .await
+            .unwrap();
+        let drop_counts: Vec<Option<i32>> = fetches.iter().map(|f| f.drop_count).collect();
+        assert_eq!(drop_counts, vec![Some(0), Some(2)]);
     }
 
     #[tokio::test]
-    async fn fetch_skips_seen_items() {
+    async fn import_export_opml() {
         let mut conn = test_conn().await.unwrap();
         let mut tx = conn.begin().await.unwrap();
 
         let user = test_user(&mut tx).await.unwrap();
 
-        // Pick a long interval (once per month) to make finding a new item less likely.
-        let mut url = lorem_rss().unwrap();
-        url = url.join("feed").unwrap();
-        url.set_query(Some("unit=month&length=5"));
-
-        let now = chrono::Utc::now();
-
-        let client = reqwest::Client::new();
-
-        let hydrant = create_hydrant(&mut tx, &user, "5 items/month", url.as_ref(), true, None)
-            .await
-            .unwrap();
+        let news = create_tag(&mut tx, &user, "News", "#ff0000").await.unwrap();
 
-        for _ in 0..2 {
-            let now = now + chrono::Duration::minutes(1);
+        create_hydrant(
+            &mut tx,
+            &user,
+            NewHydrant {
+                name: "Existing".to_string(),
+                url: "https://example.com/existing.xml".to_string(),
+                tags: Some(vec![TagSelector::Find { id: news.id }]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
-            Hydrant::fetch(&mut tx, &client, hydrant.hydrant.id, now)
-                .await
-                .unwrap();
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <opml version="2.0">
+              <body>
+                <outline text="Existing again" xmlUrl="https://example.com/existing.xml"/>
+                <outline text="Tech">
+                  <outline text="First" xmlUrl="https://example.com/first.xml" category="news"/>
+                  <outline text="Second" xmlUrl="https://example.com/second.xml" isComment="true"/>
+                  <outline text="First again" xmlUrl="https://example.com/first.xml"/>
+                </outline>
+                <outline text="Broken" xmlUrl="nope"/>
+              </body>
+            </opml>
+        "#;
+        let outlines = opml::parse(content.as_bytes()).unwrap();
+
+        let outcomes = import_opml(&mut tx, &user, outlines).await.unwrap();
+
+        let summary: Vec<(&str, &str)> = outcomes
+            .iter()
+            .map(|o| {
+                let status = match o.status {
+                    ImportStatus::Created(_) => "created",
+                    ImportStatus::Duplicate => "duplicate",
+                    ImportStatus::Invalid(_) => "invalid",
+                };
+                (o.title.as_str(), status)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Existing again", "duplicate"),
+                ("First", "created"),
+                ("Second", "created"),
+                ("First again", "duplicate"),
+                ("Broken", "invalid"),
+            ]
+        );
 
-            let found = find_hydrant(&mut tx, &user, hydrant.hydrant.id)
-                .await
-                .unwrap();
+        let hydrants = list_hydrants(&mut tx, &user).await.unwrap();
+        let summary: Vec<(&str, bool, Vec<&str>)> = hydrants
+            .iter()
+            .map(|h| {
+                let tags = h.tags.iter().map(|t| t.name.as_str()).collect();
+                (h.hydrant.name.as_str(), h.hydrant.active, tags)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Existing", true, vec!["News"]),
+                ("First", true, vec!["News", "Tech"]),
+                ("Second", false, vec!["Tech"]),
+            ]
+        );
 
-            // DB timestamps have microsecond precision, so truncate the local timestamp to the same
-            // number of subsecond digits (6).
-            assert_eq!(
-                found.hydrant.fetched_at,
-                Some(now.naive_utc().trunc_subsecs(6))
-            );
+        // Existing tags were reused and new ones were only created once.
+        let tags = list_tags(&mut tx, &user).await.unwrap();
+        assert_eq!(tags.len(), 2);
 
-            let drops = list_drops(
-                &mut tx,
-                &user,
-                DropFilters {
-                    status: Some(DropStatus::Unread),
-                    ..Default::default()
-                },
-                Some(100),
-            )
-            .await
-            .unwrap();
+        // Exporting and importing again is all duplicates.
+        let content = export_opml(&hydrants).unwrap();
+        let outlines = opml::parse(content.as_bytes()).unwrap();
+        assert_eq!(outlines.len(), 3);
 
-            assert_eq!(drops.len(), 5);
-        }
+        let outcomes = import_opml(&mut tx, &user, outlines).await.unwrap();
+        assert!(outcomes
+            .iter()
+            .all(|o| matches!(o.status, ImportStatus::Duplicate)));
     }
 }
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+// Redirects are followed by hand so that permanent ones can be noticed, but not forever.
+const MAX_REDIRECTS: usize = 10;
+
+// Requests to other sites (like fetching feeds) go wherever users point them, so none of them get
+// to take forever or send an unlimited amount of data.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub connect_timeout_seconds: u64,
+    pub timeout_seconds: u64,
+    pub max_body_bytes: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            connect_timeout_seconds: 10,
+            timeout_seconds: 30,
+            max_body_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+
+    #[error("response body is larger than {limit} bytes")]
+    TooLarge { limit: usize },
+
+    #[error("invalid redirect location: {0}")]
+    InvalidRedirect(#[from] url::ParseError),
+
+    #[error("more than {MAX_REDIRECTS} redirects")]
+    TooManyRedirects,
+}
+
+#[derive(Debug)]
+pub struct Response {
+    pub inner: reqwest::Response,
+
+    // Where the requested URL now lives for good. This is only set when every redirect up to
+    // there was a permanent one, since a temporary redirect says nothing about the future.
+    pub moved_to: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    client: reqwest::Client,
+    max_body_bytes: usize,
+}
+
+impl Client {
+    // The contact URL goes in the User-Agent so that the people running the sites being fetched
+    // can find out who's doing it.
+    pub fn new(config: &Config, contact_url: &url::Url) -> reqwest::Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Metagram/Firehose (+{})", contact_url))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            client,
+            max_body_bytes: config.max_body_bytes,
+        })
+    }
+
+    pub async fn get(&self, url: &str, mut headers: http::HeaderMap) -> Result<Response, Error> {
+        // A URL that doesn't parse fails when it's sent, so there's no origin to compare with.
+        let origin = url::Url::parse(url).ok().map(|url| url.origin());
+        let mut url = url.to_string();
+        let mut moved_to = None;
+        let mut permanent = true;
+
+        for _ in 0..=MAX_REDIRECTS {
+            let response = self
+                .client
+                .get(&url)
+                .headers(headers.clone())
+                .send()
+                .await?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(http::header::LOCATION)
+                .and_then(|location| location.to_str().ok());
+
+            let location = match (status, location) {
+                (
+                    http::StatusCode::MOVED_PERMANENTLY
+                    | http::StatusCode::FOUND
+                    | http::StatusCode::SEE_OTHER
+                    | http::StatusCode::TEMPORARY_REDIRECT
+                    | http::StatusCode::PERMANENT_REDIRECT,
+                    Some(location),
+                ) => response.url().join(location)?,
+                _ => {
+                    return Ok(Response {
+                        inner: response,
+                        moved_to,
+                    })
+                }
+            };
+
+            permanent = permanent
+                && matches!(
+                    status,
+                    http::StatusCode::MOVED_PERMANENTLY | http::StatusCode::PERMANENT_REDIRECT
+                );
+            if permanent {
+                moved_to = Some(location.to_string());
+            }
+
+            // Validators from one site mean nothing to another one, and could even match
+            // something there by accident.
+            if Some(location.origin()) != origin {
+                headers.remove(http::header::IF_NONE_MATCH);
+                headers.remove(http::header::IF_MODIFIED_SINCE);
+            }
+
+            url = location.to_string();
+        }
+
+        Err(Error::TooManyRedirects)
+    }
+
+    pub async fn post_form<T: serde::Serialize + ?Sized>(
+        &self,
+        url: &str,
+        form: &T,
+    ) -> Result<reqwest::Response, Error> {
+        Ok(self.client.post(url).form(form).send().await?)
+    }
+
+    // Read the whole body, giving up as soon as it's clear that it won't fit. The Content-Length
+    // header is only a hint, so the limit is checked while reading too.
+    pub async fn bytes(&self, mut response: reqwest::Response) -> Result<Vec<u8>, Error> {
+        let limit = self.max_body_bytes;
+
+        if let Some(length) = response.content_length() {
+            if length > limit as u64 {
+                return Err(Error::TooLarge { limit });
+            }
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(Error::TooLarge { limit });
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        let contact_url = url::Url::parse(crate::SOURCE_URL).expect("SOURCE_URL should be valid");
+        Self::new(&Config::default(), &contact_url).expect("default HTTP client should build")
+    }
+}
@@ -8,20 +8,38 @@ use tokio::task::JoinError;
 use uuid::Uuid;
 
 use crate::queue::{Context, Task};
-use crate::{firehose, queue};
+use crate::{firehose, queue, web};
 
 pub async fn cron(db: PgPool, mut shutdown: watch::Receiver<bool>) -> Result<(), JoinError> {
     // TODO: Make a real crontab instead of being relative to deploy time.
     let mut hourly = tokio::time::interval(Duration::from_secs(60 * 60));
 
+    // Hydrants keep their own schedules, so check for due ones often enough that short fetch
+    // intervals are honored.
+    let mut hydrate = tokio::time::interval(Duration::from_secs(5 * 60));
+
     tokio::spawn(async move {
         loop {
             tokio::select! {
                 _ = shutdown.changed() => break,
                 _ = hourly.tick() => {
-                    match push_cron(&db).await {
-                        Ok(_) => tracing::info!("Scheduled cron"),
-                        Err(err) => tracing::error!({ ?err }, "Failed to schedule cron"),
+                    match push_cron(&db, &Cleanup {}).await {
+                        Ok(_) => tracing::info!("Scheduled cleanup"),
+                        Err(err) => tracing::error!({ ?err }, "Failed to schedule cleanup"),
+                    }
+                    match push_cron(&db, &SubscribeAll {}).await {
+                        Ok(_) => tracing::info!("Scheduled subscriptions"),
+                        Err(err) => tracing::error!({ ?err }, "Failed to schedule subscriptions"),
+                    }
+                    match push_cron(&db, &CanonicalizeDrops {}).await {
+                        Ok(_) => tracing::info!("Scheduled canonical URLs"),
+                        Err(err) => tracing::error!({ ?err }, "Failed to schedule canonical URLs"),
+                    }
+                },
+                _ = hydrate.tick() => {
+                    match push_cron(&db, &HydrateAll {}).await {
+                        Ok(_) => tracing::info!("Scheduled hydration"),
+                        Err(err) => tracing::error!({ ?err }, "Failed to schedule hydration"),
                     }
                 },
             }
@@ -30,12 +48,11 @@ pub async fn cron(db: PgPool, mut shutdown: watch::Receiver<bool>) -> Result<(),
     .await
 }
 
-async fn push_cron(pool: &PgPool) -> anyhow::Result<()> {
+async fn push_cron(pool: &PgPool, task: &dyn Task) -> anyhow::Result<()> {
     let mut conn = pool.acquire().await?;
     let now = chrono::Utc::now();
 
-    queue::push_uniq(&mut conn, &HydrateAll {}, now).await?;
-    queue::push_uniq(&mut conn, &Cleanup {}, now).await?;
+    queue::push_uniq(&mut conn, task, now).await?;
     Ok(())
 }
 
@@ -49,12 +66,31 @@ impl Task for Cleanup {
         let now = chrono::Utc::now();
         let clear_before = now - chrono::Duration::days(7);
 
-        queue::clear_finished(&mut *ctx.tx, clear_before).await?;
+        queue::clear_finished(&mut *ctx.conn, clear_before).await?;
+
+        // Hydrant history is only useful for spotting recent trouble, so keep a bit more of it
+        // than finished jobs but don't let it grow forever.
+        let clear_fetches_before = now - chrono::Duration::days(30);
+        firehose::clear_hydrant_fetches(&mut *ctx.conn, clear_fetches_before).await?;
 
         Ok(())
     }
 }
 
+// Drops saved before canonical URLs existed get them here, since it can't be done in a migration.
+// Once they're all done, this finds nothing to do.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CanonicalizeDrops {}
+
+#[typetag::serde]
+#[async_trait]
+impl Task for CanonicalizeDrops {
+    async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        while firehose::canonicalize_drops(&mut *ctx.conn, 100).await? > 0 {}
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HydrateAll {}
 
@@ -64,13 +100,17 @@ impl Task for HydrateAll {
     async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
         let now = chrono::Utc::now();
 
-        let stale = firehose::stale_hydrants(&mut *ctx.tx, now).await?;
+        let stale = firehose::stale_hydrants(&mut *ctx.conn, now).await?;
 
         for hydrant in stale {
+            // A hydrant only gets a new attempt time once a fetch finishes, so one that's still
+            // waiting in the queue (or running) looks stale too.
             let task = HydrateOne {
                 hydrant_id: hydrant.hydrant.id,
             };
-            queue::push(&mut *ctx.tx, &task, now).await?;
+            if queue::find_pending(&mut *ctx.conn, &task).await?.is_none() {
+                queue::push(&mut *ctx.conn, &task, now).await?;
+            }
         }
 
         Ok(())
@@ -79,7 +119,7 @@ impl Task for HydrateAll {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HydrateOne {
-    hydrant_id: Uuid,
+    pub hydrant_id: Uuid,
 }
 
 #[typetag::serde]
@@ -87,8 +127,84 @@ pub struct HydrateOne {
 impl Task for HydrateOne {
     async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
         let now = chrono::Utc::now();
-        let client = reqwest::Client::new();
 
-        firehose::Hydrant::fetch(&mut *ctx.tx, &client, self.hydrant_id, now).await
+        firehose::Hydrant::fetch(&mut *ctx.conn, ctx.http, self.hydrant_id, now).await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscribeAll {}
+
+#[typetag::serde]
+#[async_trait]
+impl Task for SubscribeAll {
+    async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let now = chrono::Utc::now();
+
+        let due = firehose::due_subscriptions(&mut *ctx.conn, now).await?;
+
+        for subscription in due {
+            let task = SubscribeOne {
+                hydrant_id: subscription.hydrant_id,
+            };
+            queue::push(&mut *ctx.conn, &task, now).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscribeOne {
+    pub hydrant_id: Uuid,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl Task for SubscribeOne {
+    async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let now = chrono::Utc::now();
+
+        // The feed might have dropped its hub since this was queued.
+        let subscription =
+            match firehose::find_subscription(&mut *ctx.conn, self.hydrant_id).await? {
+                Some(subscription) => subscription,
+                None => return Ok(()),
+            };
+
+        let callback = ctx
+            .base_url
+            .join(&web::hydrants::WebSub::path(&self.hydrant_id))?;
+
+        firehose::request_subscription(&mut *ctx.conn, ctx.http, &subscription, &callback, now)
+            .await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchDropMetadata {
+    pub drop_id: Uuid,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl Task for FetchDropMetadata {
+    async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let now = chrono::Utc::now();
+
+        firehose::fetch_drop_metadata(&mut *ctx.conn, ctx.http, self.drop_id, now).await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractDropArticle {
+    pub drop_id: Uuid,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl Task for ExtractDropArticle {
+    async fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        firehose::extract_drop_article(&mut *ctx.conn, ctx.http, self.drop_id).await
     }
 }
@@ -28,10 +28,16 @@ pub use auth::{Auth, AuthN};
 
 pub mod firehose;
 
+mod feeds;
 mod filters;
+pub mod http_client;
 pub mod jobs;
+mod opml;
+mod pages;
 pub mod queue;
+mod urls;
 mod web;
+mod websub;
 
 const COMMIT_HASH: &str = include_str!(concat!(env!("OUT_DIR"), "/commit_hash"));
 const BUILD_PROFILE: &str = include_str!(concat!(env!("OUT_DIR"), "/build_profile"));
@@ -69,6 +75,7 @@ pub struct ServerConfig {
     pub base_url: url::Url,
     pub cookie_key: cookie::Key,
     pub database_pool: PgPool,
+    pub http_client: http_client::Client,
 }
 
 pub struct Server {
@@ -81,6 +88,7 @@ pub struct AppState {
     database_pool: PgPool,
     cookie_key: cookie::Key,
     auth: Auth,
+    http_client: http_client::Client,
 }
 
 impl Server {
@@ -90,6 +98,7 @@ impl Server {
             database_pool: config.database_pool,
             cookie_key: config.cookie_key.clone(),
             auth: config.auth,
+            http_client: config.http_client,
         };
 
         let router = Router::new()
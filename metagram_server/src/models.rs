@@ -54,6 +54,18 @@ pub struct Drop {
     pub status: DropStatus,
     pub moved_at: Timestamp,
     pub hydrant_id: Option<Uuid>,
+
+    // What the page says about itself, once it's been fetched.
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub image_url: Option<String>,
+    pub canonical_link: Option<String>,
+    pub metadata_fetched_at: Option<Timestamp>,
+
+    // Only ever shown to the drop's owner. Notes are Markdown.
+    pub notes: Option<String>,
+    pub highlights: Vec<String>,
+
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
@@ -86,6 +98,15 @@ impl Drop {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DropArticle {
+    pub drop_id: Uuid,
+    pub html: String,
+    pub word_count: i32,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, Decode)]
 pub struct Tag {
     pub id: Uuid,
@@ -113,6 +134,7 @@ pub struct Stream {
     pub user_id: Uuid,
     pub name: String,
     pub tag_ids: Vec<Uuid>,
+    pub max_reading_minutes: Option<i32>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
@@ -126,6 +148,124 @@ pub struct Hydrant {
     pub active: bool,
     pub tag_ids: Vec<Uuid>,
     pub fetched_at: Option<Timestamp>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetch_interval_minutes: i32,
+    pub failure_count: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<Timestamp>,
+    pub include_rules: Vec<String>,
+    pub exclude_rules: Vec<String>,
+    pub source_kind: HydrantSourceKind,
+    pub first_fetch_limit: Option<i32>,
+    pub max_drops_per_fetch: Option<i32>,
+    pub backfill_read: bool,
+    // Set when the hydrant turned itself off, rather than its owner doing it.
+    pub deactivated_for: Option<HydrantEventKind>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl Hydrant {
+    pub const DEFAULT_FETCH_INTERVAL_MINUTES: i32 = 60;
+    pub const DEFAULT_FIRST_FETCH_LIMIT: i32 = 20;
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "hydrant_source_kind", rename_all = "lowercase")]
+pub enum HydrantSourceKind {
+    #[default]
+    Feed,
+    Sitemap,
+}
+
+impl HydrantSourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Feed => "Feed",
+            Self::Sitemap => "Sitemap",
+        }
+    }
+}
+
+impl std::fmt::Display for HydrantSourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            Self::Feed => "feed",
+            Self::Sitemap => "sitemap",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct HydrantFetch {
+    pub id: Uuid,
+    pub hydrant_id: Uuid,
+    pub fetched_at: Timestamp,
+    pub status: Option<i32>,
+    pub bytes: Option<i64>,
+    pub item_count: Option<i32>,
+    pub drop_count: Option<i32>,
+    pub error: Option<String>,
+    pub filtered_count: Option<i32>,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "hydrant_event_kind", rename_all = "lowercase")]
+pub enum HydrantEventKind {
+    Moved,
+    Discovered,
+    Gone,
+    Failing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct HydrantEvent {
+    pub id: Uuid,
+    pub hydrant_id: Uuid,
+    pub kind: HydrantEventKind,
+    pub old_url: Option<String>,
+    pub new_url: Option<String>,
+    pub occurred_at: Timestamp,
+    pub created_at: Timestamp,
+}
+
+impl HydrantEvent {
+    pub fn description(&self) -> String {
+        let old_url = self.old_url.as_deref().unwrap_or_default();
+        let new_url = self.new_url.as_deref().unwrap_or_default();
+
+        match self.kind {
+            HydrantEventKind::Moved => {
+                format!("The feed moved permanently from {} to {}", old_url, new_url)
+            }
+            HydrantEventKind::Discovered => {
+                format!("Switched from the page {} to its feed {}", old_url, new_url)
+            }
+            HydrantEventKind::Gone => {
+                format!(
+                    "Turned off because {} kept saying the feed is gone",
+                    old_url
+                )
+            }
+            HydrantEventKind::Failing => "Turned off after too many failed fetches".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct HydrantSubscription {
+    pub id: Uuid,
+    pub hydrant_id: Uuid,
+    pub hub_url: String,
+    pub topic_url: String,
+    pub secret: String,
+    pub requested_at: Option<Timestamp>,
+    pub lease_expires_at: Option<Timestamp>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
@@ -160,6 +300,13 @@ mod tests {
             status: DropStatus::Unread,
             moved_at: now,
             hydrant_id: None,
+            description: None,
+            site_name: None,
+            image_url: None,
+            canonical_link: None,
+            metadata_fetched_at: None,
+            notes: None,
+            highlights: Vec::new(),
             created_at: now,
             updated_at: now,
         }
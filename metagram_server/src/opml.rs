@@ -0,0 +1,296 @@
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+// OPML is the usual way to move feed subscriptions between readers. Only the parts that map onto
+// hydrants are supported: one outline per feed, with its tags as categories. Folders (outlines
+// without a feed URL) are flattened, and their names become categories of everything in them.
+//
+// OPML has no notion of an inactive subscription, so those get written as comments (isComment),
+// which is the closest thing in the spec.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outline {
+    pub title: String,
+    pub xml_url: String,
+    pub active: bool,
+    pub categories: Vec<String>,
+}
+
+// Something that looked like a subscription but couldn't be read as one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidOutline {
+    pub title: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("not an OPML document")]
+    NotOpml,
+
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+}
+
+pub fn write(title: &str, outlines: &[Outline]) -> Result<String, Error> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(
+        BytesStart::new("opml").with_attributes([("version", "2.0")]),
+    ))?;
+
+    writer.write_event(Event::Start(BytesStart::new("head")))?;
+    writer.write_event(Event::Start(BytesStart::new("title")))?;
+    writer.write_event(Event::Text(BytesText::new(title)))?;
+    writer.write_event(Event::End(BytesEnd::new("title")))?;
+    writer.write_event(Event::End(BytesEnd::new("head")))?;
+
+    writer.write_event(Event::Start(BytesStart::new("body")))?;
+    for outline in outlines {
+        let mut element = BytesStart::new("outline");
+        element.push_attribute(("type", "rss"));
+        element.push_attribute(("text", outline.title.as_str()));
+        element.push_attribute(("title", outline.title.as_str()));
+        element.push_attribute(("xmlUrl", outline.xml_url.as_str()));
+
+        if !outline.categories.is_empty() {
+            element.push_attribute(("category", outline.categories.join(",").as_str()));
+        }
+        if !outline.active {
+            element.push_attribute(("isComment", "true"));
+        }
+
+        writer.write_event(Event::Empty(element))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new("body")))?;
+
+    writer.write_event(Event::End(BytesEnd::new("opml")))?;
+
+    // Everything written above came from &str, so this is still UTF-8.
+    Ok(String::from_utf8(writer.into_inner()).unwrap())
+}
+
+pub fn parse(content: &[u8]) -> Result<Vec<Result<Outline, InvalidOutline>>, Error> {
+    let mut reader = Reader::from_reader(content);
+    reader.trim_text(true);
+
+    let mut outlines = Vec::new();
+
+    // Each open outline element pushes the categories (and comment state) that its children
+    // inherit.
+    let mut parents: Vec<(Vec<String>, bool)> = Vec::new();
+
+    let mut seen_opml = false;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"opml" => seen_opml = true,
+            Event::Empty(e) if e.name().as_ref() == b"opml" => seen_opml = true,
+            Event::Start(e) if e.name().as_ref() == b"outline" => {
+                let attrs = OutlineAttrs::read(&reader, &e)?;
+                let (categories, comment) = attrs.inherit(parents.last());
+
+                if let Some(outline) = attrs.outline(&categories, comment) {
+                    outlines.push(outline);
+                }
+
+                // A folder's name only applies to what's inside it.
+                let mut categories = categories;
+                if attrs.xml_url.is_none() {
+                    categories.extend(attrs.title());
+                }
+                parents.push((categories, comment));
+            }
+            Event::Empty(e) if e.name().as_ref() == b"outline" => {
+                let attrs = OutlineAttrs::read(&reader, &e)?;
+                let (categories, comment) = attrs.inherit(parents.last());
+
+                if let Some(outline) = attrs.outline(&categories, comment) {
+                    outlines.push(outline);
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"outline" => {
+                parents.pop();
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    if !seen_opml {
+        return Err(Error::NotOpml);
+    }
+
+    Ok(outlines)
+}
+
+#[derive(Debug, Default)]
+struct OutlineAttrs {
+    text: Option<String>,
+    title: Option<String>,
+    xml_url: Option<String>,
+    category: Option<String>,
+    is_comment: bool,
+}
+
+impl OutlineAttrs {
+    fn read(reader: &Reader<&[u8]>, element: &BytesStart) -> Result<Self, Error> {
+        let mut attrs = Self::default();
+
+        for attr in element.attributes() {
+            let attr = attr.map_err(quick_xml::Error::from)?;
+            let value = attr.decode_and_unescape_value(reader)?.trim().to_string();
+
+            match attr.key.as_ref() {
+                b"text" => attrs.text = Some(value),
+                b"title" => attrs.title = Some(value),
+                b"xmlUrl" => attrs.xml_url = Some(value),
+                b"category" => attrs.category = Some(value),
+                b"isComment" => attrs.is_comment = value == "true",
+                _ => (),
+            }
+        }
+
+        Ok(attrs)
+    }
+
+    fn title(&self) -> Option<String> {
+        self.title
+            .iter()
+            .chain(self.text.iter())
+            .find(|s| !s.is_empty())
+            .cloned()
+    }
+
+    // Combine the parent's categories and comment state with this outline's own.
+    fn inherit(&self, parent: Option<&(Vec<String>, bool)>) -> (Vec<String>, bool) {
+        let (mut categories, comment) = parent.cloned().unwrap_or_default();
+
+        // Categories are comma-separated paths like "/Tech/Rust". Each one becomes a single tag,
+        // named for the whole path minus the leading slash.
+        for category in self.category.iter().flat_map(|c| c.split(',')) {
+            let category = category.trim().trim_start_matches('/').trim();
+            if !category.is_empty() && !categories.iter().any(|c| c == category) {
+                categories.push(category.to_string());
+            }
+        }
+
+        (categories, comment || self.is_comment)
+    }
+
+    fn outline(
+        &self,
+        categories: &[String],
+        comment: bool,
+    ) -> Option<Result<Outline, InvalidOutline>> {
+        let xml_url = match &self.xml_url {
+            Some(url) => url,
+            // Folders aren't subscriptions, so there's nothing to report.
+            None => return None,
+        };
+
+        if let Err(err) = url::Url::parse(xml_url) {
+            return Some(Err(InvalidOutline {
+                title: self.title(),
+                reason: format!("invalid feed URL: {}", err),
+            }));
+        }
+
+        Some(Ok(Outline {
+            title: self.title().unwrap_or_else(|| xml_url.clone()),
+            xml_url: xml_url.clone(),
+            active: !comment,
+            categories: categories.to_vec(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let outlines = vec![
+            Outline {
+                title: "Lorem & Ipsum".to_string(),
+                xml_url: "https://example.com/feed?a=1&b=2".to_string(),
+                active: true,
+                categories: vec!["News".to_string(), "Tech".to_string()],
+            },
+            Outline {
+                title: "Dolor".to_string(),
+                xml_url: "https://example.com/dolor.xml".to_string(),
+                active: false,
+                categories: vec![],
+            },
+        ];
+
+        let content = write("Hydrants", &outlines).unwrap();
+        let parsed = parse(content.as_bytes()).unwrap();
+
+        let expected: Vec<Result<Outline, InvalidOutline>> = outlines.into_iter().map(Ok).collect();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn parse_folders() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <opml version="2.0">
+              <head><title>Subscriptions</title></head>
+              <body>
+                <outline text="Tech" title="Tech">
+                  <outline type="rss" text="Rust" xmlUrl="https://example.com/rust.xml" category="/Programming, /Tech"/>
+                  <outline text="Broken" xmlUrl="not a url"/>
+                </outline>
+                <outline text="Old stuff" isComment="true">
+                  <outline type="rss" xmlUrl="https://example.com/old.xml"/>
+                </outline>
+                <outline type="rss" text="Top" xmlUrl="https://example.com/top.xml"/>
+              </body>
+            </opml>
+        "#;
+
+        let parsed = parse(content.as_bytes()).unwrap();
+
+        assert_eq!(
+            parsed,
+            vec![
+                Ok(Outline {
+                    title: "Rust".to_string(),
+                    xml_url: "https://example.com/rust.xml".to_string(),
+                    active: true,
+                    categories: vec!["Tech".to_string(), "Programming".to_string()],
+                }),
+                Err(InvalidOutline {
+                    title: Some("Broken".to_string()),
+                    reason: "invalid feed URL: relative URL without a base".to_string(),
+                }),
+                Ok(Outline {
+                    title: "https://example.com/old.xml".to_string(),
+                    xml_url: "https://example.com/old.xml".to_string(),
+                    active: false,
+                    categories: vec!["Old stuff".to_string()],
+                }),
+                Ok(Outline {
+                    title: "Top".to_string(),
+                    xml_url: "https://example.com/top.xml".to_string(),
+                    active: true,
+                    categories: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_not_opml() {
+        let res = parse(b"<rss version=\"2.0\"><channel></channel></rss>");
+        assert!(matches!(res, Err(Error::NotOpml)));
+
+        let res = parse(b"<opml><body><outline text=\"unclosed\"></body></opml>");
+        assert!(matches!(res, Err(Error::Xml(_))));
+    }
+}
@@ -0,0 +1,388 @@
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+use url::Url;
+
+use crate::http_client;
+
+// Web pages that drops point to, as opposed to the feeds that hydrants read.
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Http(#[from] http_client::Error),
+
+    #[error("not an HTML page: {0}")]
+    NotHtml(String),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    // Where the page ended up, after any redirects.
+    pub url: Url,
+    pub content: Vec<u8>,
+}
+
+pub async fn fetch(client: &http_client::Client, url: &str) -> Result<Page, Error> {
+    let response = client.get(url, Default::default()).await?;
+    let response = response.inner.error_for_status()?;
+
+    // Plenty of servers don't bother with a Content-Type, so only turn away ones that say they're
+    // something else.
+    let content_type = response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_ascii_lowercase());
+
+    if let Some(content_type) = content_type {
+        if !(content_type.starts_with("text/html")
+            || content_type.starts_with("application/xhtml+xml"))
+        {
+            return Err(Error::NotHtml(content_type));
+        }
+    }
+
+    let url = response.url().clone();
+    let content = client.bytes(response).await?;
+
+    Ok(Page { url, content })
+}
+
+// What a page says about itself, mostly from OpenGraph tags, with plain HTML as a fallback.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub canonical_url: Option<String>,
+    pub image_url: Option<String>,
+}
+
+pub fn metadata(page: &Page) -> Metadata {
+    let html = String::from_utf8_lossy(&page.content);
+    let document = kuchikiki::parse_html().one(html.as_ref());
+
+    let meta = |names: &[&str]| names.iter().find_map(|name| meta_content(&document, name));
+    // These end up as links and images on the drop, so anything but a web URL (like a
+    // `javascript:` one) is thrown out.
+    let link = |url: Option<String>| {
+        url.and_then(|url| page.url.join(&url).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+    };
+
+    let title = meta(&["og:title", "twitter:title"]).or_else(|| {
+        document
+            .select_first("title")
+            .ok()
+            .and_then(|title| present(title.text_contents()))
+    });
+
+    let canonical_url = document
+        .select("link[rel][href]")
+        .ok()
+        .and_then(|mut links| {
+            links.find_map(|link| {
+                let attrs = link.attributes.borrow();
+                let canonical = attrs
+                    .get("rel")?
+                    .split_ascii_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("canonical"));
+
+                if canonical {
+                    attrs.get("href").map(str::to_string)
+                } else {
+                    None
+                }
+            })
+        })
+        .or_else(|| meta(&["og:url"]));
+
+    Metadata {
+        title,
+        description: meta(&["og:description", "twitter:description", "description"]),
+        site_name: meta(&["og:site_name", "application-name"]),
+        canonical_url: link(canonical_url).map(String::from),
+        image_url: link(meta(&["og:image", "og:image:url", "twitter:image"])).map(String::from),
+    }
+}
+
+// Elements that are never part of an article, along with everything in them.
+const BOILERPLATE: &str =
+    "script, style, noscript, template, nav, header, footer, aside, form, iframe, svg, button";
+
+// Paragraphs shorter than this are usually captions, bylines, or buttons.
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Article {
+    // Sanitized, so it's safe to show as-is.
+    pub html: String,
+    pub word_count: i32,
+}
+
+// A typical adult reading speed for prose. Reading times are only ever estimates anyway.
+pub const WORDS_PER_MINUTE: i32 = 200;
+
+// How many minutes an article takes to read, rounded up so that nothing takes no time at all.
+pub fn reading_minutes(word_count: i32) -> i32 {
+    (word_count.max(1) + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE
+}
+
+// The main text of a page, found roughly the way Readability does it: whatever holds the most
+// paragraph text wins.
+pub fn article(page: &Page) -> Option<Article> {
+    let html = String::from_utf8_lossy(&page.content);
+    let document = kuchikiki::parse_html().one(html.as_ref());
+
+    // Detaching while selecting would skip elements, so find them all first.
+    let boilerplate: Vec<_> = document.select(BOILERPLATE).ok()?.collect();
+    for element in boilerplate {
+        element.as_node().detach();
+    }
+
+    let content = main_content(&document)?;
+
+    let word_count = content.text_contents().split_whitespace().count();
+    if word_count == 0 {
+        return None;
+    }
+
+    let inner: String = content.children().map(|child| child.to_string()).collect();
+    let html = ammonia::Builder::default()
+        .url_relative(ammonia::UrlRelative::RewriteWithBase(page.url.clone()))
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .clean(&inner)
+        .to_string();
+
+    Some(Article {
+        html,
+        word_count: word_count.try_into().ok()?,
+    })
+}
+
+fn main_content(document: &NodeRef) -> Option<NodeRef> {
+    // Pages that mark up where their content is usually mean it.
+    for selector in ["article", "main", "[role=main]"] {
+        if let Ok(element) = document.select_first(selector) {
+            return Some(element.as_node().clone());
+        }
+    }
+
+    // Otherwise, score elements by the paragraph text right inside them. The element around that
+    // gets some credit too, for articles split into sections.
+    let mut scores: Vec<(NodeRef, usize)> = Vec::new();
+    let mut score = |node: NodeRef, points: usize| match scores
+        .iter_mut()
+        .find(|(scored, _)| *scored == node)
+    {
+        Some((_, total)) => *total += points,
+        None => scores.push((node, points)),
+    };
+
+    for paragraph in document.select("p").ok()? {
+        let length = paragraph.text_contents().trim().len();
+        if length < MIN_PARAGRAPH_LENGTH {
+            continue;
+        }
+
+        if let Some(parent) = paragraph.as_node().parent() {
+            if let Some(grandparent) = parent.parent() {
+                score(grandparent, length / 2);
+            }
+            score(parent, length);
+        }
+    }
+
+    scores
+        .into_iter()
+        .max_by_key(|(_, total)| *total)
+        .map(|(node, _)| node)
+        .or_else(|| {
+            document
+                .select_first("body")
+                .ok()
+                .map(|body| body.as_node().clone())
+        })
+}
+
+// OpenGraph uses `property` where everything else uses `name`, but plenty of pages mix them up.
+fn meta_content(document: &NodeRef, name: &str) -> Option<String> {
+    document.select("meta[content]").ok()?.find_map(|meta| {
+        let attrs = meta.attributes.borrow();
+
+        let matches = ["property", "name"].iter().any(|key| {
+            attrs
+                .get(*key)
+                .map(|value| value.trim().eq_ignore_ascii_case(name))
+                .unwrap_or(false)
+        });
+
+        if matches {
+            attrs.get("content").map(str::to_string).and_then(present)
+        } else {
+            None
+        }
+    })
+}
+
+fn present(s: String) -> Option<String> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(content: &str) -> Page {
+        Page {
+            url: Url::parse("https://example.com/articles/lorem").unwrap(),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn opengraph_metadata() {
+        let page = page(
+            r#"<!DOCTYPE html>
+            <html>
+              <head>
+                <title>Lorem | Example</title>
+                <meta property="og:title" content=" Lorem Ipsum ">
+                <meta property="og:description" content="Dolor sit amet.">
+                <meta property="og:site_name" content="Example">
+                <meta property="og:image" content="/images/lorem.png">
+                <link rel="canonical" href="https://example.com/lorem">
+              </head>
+              <body></body>
+            </html>
+            "#,
+        );
+
+        assert_eq!(
+            metadata(&page),
+            Metadata {
+                title: Some("Lorem Ipsum".to_string()),
+                description: Some("Dolor sit amet.".to_string()),
+                site_name: Some("Example".to_string()),
+                canonical_url: Some("https://example.com/lorem".to_string()),
+                image_url: Some("https://example.com/images/lorem.png".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn plain_metadata() {
+        let plain = page(
+            r#"<html>
+              <head>
+                <title>
+                  Lorem Ipsum
+                </title>
+                <meta name="description" content="Dolor sit amet.">
+                <meta name="og:url" content="canonical">
+                <meta name="twitter:image" content="">
+              </head>
+            </html>
+            "#,
+        );
+
+        assert_eq!(
+            metadata(&plain),
+            Metadata {
+                title: Some("Lorem Ipsum".to_string()),
+                description: Some("Dolor sit amet.".to_string()),
+                site_name: None,
+                canonical_url: Some("https://example.com/articles/canonical".to_string()),
+                image_url: None,
+            }
+        );
+
+        assert_eq!(metadata(&page("just some text")), Metadata::default());
+    }
+
+    #[test]
+    fn metadata_only_links_to_the_web() {
+        let sneaky = page(
+            r#"<html>
+              <head>
+                <link rel="canonical" href="javascript:alert('hi')">
+                <meta property="og:image" content="data:image/png;base64,AAAA">
+              </head>
+            </html>
+            "#,
+        );
+
+        assert_eq!(metadata(&sneaky), Metadata::default());
+    }
+
+    #[test]
+    fn estimate_reading_minutes() {
+        assert_eq!(reading_minutes(0), 1);
+        assert_eq!(reading_minutes(1), 1);
+        assert_eq!(reading_minutes(WORDS_PER_MINUTE), 1);
+        assert_eq!(reading_minutes(WORDS_PER_MINUTE + 1), 2);
+        assert_eq!(reading_minutes(WORDS_PER_MINUTE * 12), 12);
+    }
+
+    #[test]
+    fn extract_articles() {
+        let marked_up = page(
+            r#"<html>
+              <body>
+                <nav><a href="/">Home</a></nav>
+                <article>
+                  <h1>Lorem</h1>
+                  <p onclick="alert('hi')">Lorem ipsum <a href="/dolor">dolor</a> sit amet.</p>
+                  <script>alert("hi")</script>
+                  <img src="lorem.png" alt="Lorem">
+                </article>
+                <footer>Copyright</footer>
+              </body>
+            </html>"#,
+        );
+
+        let found = article(&marked_up).unwrap();
+        assert_eq!(found.word_count, 6);
+        assert!(found.html.contains("<h1>Lorem</h1>"));
+        assert!(found.html.contains(
+            r#"<a href="https://example.com/dolor" rel="noopener noreferrer nofollow">dolor</a>"#
+        ));
+        assert!(found
+            .html
+            .contains(r#"<img src="https://example.com/articles/lorem.png" alt="Lorem">"#));
+        assert!(!found.html.contains("onclick"));
+        assert!(!found.html.contains("alert"));
+        assert!(!found.html.contains("Home"));
+        assert!(!found.html.contains("Copyright"));
+
+        let unmarked = page(
+            r#"<html>
+              <body>
+                <div class="sidebar"><p>Subscribe to the newsletter for more posts!</p></div>
+                <div class="content">
+                  <p>Lorem ipsum dolor sit amet, consectetur adipiscing elit.</p>
+                  <p>Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>
+                </div>
+              </body>
+            </html>"#,
+        );
+
+        let found = article(&unmarked).unwrap();
+        assert_eq!(found.word_count, 19);
+        assert!(!found.html.contains("newsletter"));
+
+        assert_eq!(article(&page("<html><body></body></html>")), None);
+    }
+}
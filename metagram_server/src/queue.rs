@@ -11,16 +11,32 @@ use tokio::sync::watch;
 use tokio::task::JoinError;
 use uuid::Uuid;
 
-type PgTransaction<'tx> = sqlx::Transaction<'tx, sqlx::Postgres>;
+use crate::http_client;
+
+// A job that was claimed but never finished, probably because its worker stopped partway through,
+// can be claimed again after this long.
+const CLAIM_TIMEOUT_MINUTES: i64 = 60;
 
 pub struct Worker {
     db: PgPool,
     interval: Duration,
+    http: http_client::Client,
+    base_url: url::Url,
 }
 
 impl Worker {
-    pub fn new(db: PgPool, interval: Duration) -> Self {
-        Self { db, interval }
+    pub fn new(
+        db: PgPool,
+        interval: Duration,
+        http: http_client::Client,
+        base_url: url::Url,
+    ) -> Self {
+        Self {
+            db,
+            interval,
+            http,
+            base_url,
+        }
     }
 
     pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<(), JoinError> {
@@ -68,11 +84,19 @@ impl Worker {
                     }
                 };
 
+                // Jobs often wait on other servers, so they run outside the claim's transaction.
+                // Otherwise, a slow request would keep a transaction (and a lock on the job) open
+                // the whole time.
+                if let Err(err) = tx.commit().await {
+                    tracing::error!({ ?err }, "Failed to commit job claim");
+                    continue;
+                }
+
                 found_job = true;
 
                 // Don't select! with the shutdown signal here. Jobs should be relatively
                 // short-lived, so give this one a chance to complete before checking again.
-                match Self::run_next_job(tx, job).await {
+                match Self::run_next_job(&self.db, &self.http, &self.base_url, job).await {
                     Ok(_) => (),
                     Err(err) => tracing::error!({ ?err }, "Worker failed to run job"),
                 }
@@ -81,24 +105,44 @@ impl Worker {
         .await
     }
 
-    async fn run_next_job(mut tx: PgTransaction<'_>, job: Job) -> anyhow::Result<()> {
+    async fn run_next_job(
+        db: &PgPool,
+        http: &http_client::Client,
+        base_url: &url::Url,
+        job: Job,
+    ) -> anyhow::Result<()> {
         tracing::info!({ ?job }, "Running job");
 
-        let task: Box<dyn Task> = serde_json::from_value(job.params.clone())?;
-        let mut ctx = Context { tx: &mut tx };
+        let mut conn = db.acquire().await?;
+
+        // A job that can't be read can't be run either, but it still has to finish so that it
+        // isn't claimed again.
+        let task: Box<dyn Task> = match serde_json::from_value(job.params.clone()) {
+            Ok(task) => task,
+            Err(err) => {
+                tracing::error!({ ?job, ?err }, "Job could not be read");
+                mark_failure(&mut conn, job, chrono::Utc::now(), err.to_string()).await?;
+                return Ok(());
+            }
+        };
+
+        let mut ctx = Context {
+            conn: &mut conn,
+            http,
+            base_url,
+        };
 
         match task.run(&mut ctx).await {
             Ok(_) => {
                 tracing::info!({ ?job }, "Job succeeded");
-                mark_success(&mut tx, job, chrono::Utc::now()).await?;
+                mark_success(&mut conn, job, chrono::Utc::now()).await?;
             }
             Err(err) => {
                 tracing::error!({ ?job, ?err }, "Job failed");
-                mark_failure(&mut tx, job, chrono::Utc::now(), err.to_string()).await?;
+                mark_failure(&mut conn, job, chrono::Utc::now(), err.to_string()).await?;
             }
         }
 
-        tx.commit().await?;
         Ok(())
     }
 }
@@ -109,8 +153,13 @@ pub trait Task: Send + Sync {
     async fn run(&self, ctx: &mut Context) -> anyhow::Result<()>;
 }
 
+// Everything a task has to work with. Apart from the connection, these are shared by all the jobs a
+// worker runs. The connection isn't in a transaction, so tasks open their own around anything that
+// has to happen all together.
 pub struct Context<'a> {
-    pub tx: &'a mut PgConnection,
+    pub conn: &'a mut PgConnection,
+    pub http: &'a http_client::Client,
+    pub base_url: &'a url::Url,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
@@ -123,6 +172,12 @@ pub struct Job {
     pub error: Option<String>,
 }
 
+impl Job {
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some() && self.finished_at.is_none()
+    }
+}
+
 pub async fn push(
     conn: &mut PgConnection,
     task: &dyn Task,
@@ -148,6 +203,26 @@ pub async fn push_uniq(
     Ok(job)
 }
 
+// Find an unfinished job for exactly this task, e.g. to show whether it's still waiting to run.
+pub async fn find_pending(conn: &mut PgConnection, task: &dyn Task) -> anyhow::Result<Option<Job>> {
+    let params = serde_json::to_value(task)?;
+
+    let res = sqlx::query_as!(
+        Job,
+        "
+        select * from jobs
+        where params = $1
+        and finished_at is null
+        order by scheduled_at asc
+        limit 1
+        ",
+        params,
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(res)
+}
+
 async fn find_job_by_type(conn: &mut PgConnection, type_name: &str) -> anyhow::Result<Option<Job>> {
     let res = sqlx::query_as!(
         Job,
@@ -199,6 +274,7 @@ pub async fn claim_job(
         where id in (
             select id from jobs
             where started_at is null
+            or (finished_at is null and started_at < $2)
             order by scheduled_at asc
             for update skip locked
             limit 1
@@ -206,6 +282,7 @@ pub async fn claim_job(
         returning *
         ",
         now.naive_utc(),
+        (now - chrono::Duration::minutes(CLAIM_TIMEOUT_MINUTES)).naive_utc(),
     )
     .fetch_optional(conn)
     .await
@@ -0,0 +1,147 @@
+use url::Url;
+
+// The same page can show up under lots of URLs: with tracking parameters tacked on, with a
+// fragment pointing somewhere in it, or wrapped in a link shortener or redirector. Drops are
+// matched up by a canonical form of their URL that leaves all of that out, so saving something
+// twice doesn't make two drops.
+
+// Query parameters that only say how someone got to a page.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "twclid", "igshid",
+    "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "ref_src", "ref_url",
+];
+
+const TRACKING_PREFIXES: &[&str] = &["utm_"];
+
+// Redirectors can wrap each other, but not forever.
+const MAX_UNWRAPS: usize = 5;
+
+// The canonical form of a URL. Anything that doesn't parse as one is only trimmed, since there's
+// nothing else to be sure of.
+pub fn canonicalize(url: &str) -> String {
+    let url = url.trim();
+
+    let mut parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.to_string(),
+    };
+
+    for _ in 0..MAX_UNWRAPS {
+        match unwrap(&parsed) {
+            Some(target) => parsed = target,
+            None => break,
+        }
+    }
+
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return parsed.to_string();
+    }
+
+    // Parsing already lowercases the host (and leaves out default ports), but a trailing dot
+    // means the same host too.
+    if let Some(host) = parsed.host_str() {
+        if let Some(host) = host.strip_suffix('.') {
+            let host = host.to_string();
+            // This can't fail: it's the same host, minus the dot.
+            parsed.set_host(Some(&host)).ok();
+        }
+    }
+
+    parsed.set_fragment(None);
+
+    let params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    if params.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    parsed.to_string()
+}
+
+fn is_tracking(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+
+    TRACKING_PARAMS.contains(&key.as_str())
+        || TRACKING_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+}
+
+// Where a known shortener or redirector URL points, if it's clear from the URL alone.
+fn unwrap(url: &Url) -> Option<Url> {
+    let host = url.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let path = url.path();
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| Url::parse(&value).ok())
+    };
+
+    // The first path segment, which is all these shorteners use.
+    let id = || {
+        url.path_segments()?
+            .next()
+            .filter(|id| !id.is_empty())
+            .map(String::from)
+    };
+
+    match host {
+        "google.com" if path == "/url" => param("q").or_else(|| param("url")),
+        "l.facebook.com" | "lm.facebook.com" if path == "/l.php" => param("u"),
+        "l.instagram.com" => param("u"),
+        "out.reddit.com" => param("url"),
+        "t.umblr.com" if path == "/redirect" => param("z"),
+        "youtube.com" if path == "/redirect" => param("q"),
+        "youtu.be" => {
+            let mut target = Url::parse("https://www.youtube.com/watch").ok()?;
+            target.query_pairs_mut().append_pair("v", &id()?);
+            Some(target)
+        }
+        "redd.it" => Url::parse(&format!("https://www.reddit.com/comments/{}", id()?)).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_urls() {
+        let cases = [
+            ("https://Example.COM/Path?b=2&a=1", "https://example.com/Path?b=2&a=1"),
+            ("  https://example.com:443/  ", "https://example.com/"),
+            ("https://example.com./lorem", "https://example.com/lorem"),
+            ("https://example.com/lorem#ipsum", "https://example.com/lorem"),
+            (
+                "https://example.com/lorem?utm_source=rss&id=3&UTM_Medium=feed&fbclid=abc",
+                "https://example.com/lorem?id=3",
+            ),
+            ("https://example.com/?utm_campaign=x", "https://example.com/"),
+            (
+                "https://www.google.com/url?q=https%3A%2F%2Fexample.com%2Florem%3Futm_source%3Dgoogle&sa=D",
+                "https://example.com/lorem",
+            ),
+            (
+                "https://l.facebook.com/l.php?u=https%3A%2F%2Fexample.com%2Fipsum&h=xyz",
+                "https://example.com/ipsum",
+            ),
+            ("https://youtu.be/dQw4w9WgXcQ?t=42", "https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            ("https://redd.it/abc123", "https://www.reddit.com/comments/abc123"),
+            ("mailto:someone@example.com", "mailto:someone@example.com"),
+            ("not a url", "not a url"),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(canonicalize(url), expected, "canonicalize({:?})", url);
+        }
+    }
+}
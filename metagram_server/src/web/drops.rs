@@ -15,15 +15,15 @@ use http::HeaderValue;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::Deserialize;
-use sqlx::Acquire;
+use sqlx::{Acquire, PgConnection};
 use uuid::Uuid;
 
-use crate::firehose;
-use crate::models::{DropStatus, Tag, User};
+use crate::models::{DropArticle, DropStatus, Tag, User};
 use crate::{
     filters,
     view_models::{tag_options, TagOption},
 };
+use crate::{firehose, jobs, queue};
 use crate::{AppState, BaseUrl, Context, PgConn, Session};
 
 pub fn router() -> Router<AppState> {
@@ -35,6 +35,8 @@ pub fn router() -> Router<AppState> {
         .typed_get(edit)
         .typed_post(update)
         .typed_post(r#move)
+        .typed_get(reader)
+        .typed_post(extract)
 }
 
 #[derive(TypedPath, Deserialize)]
@@ -83,6 +85,18 @@ impl Move {
     }
 }
 
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/drops/:id/reader")]
+pub struct Reader {
+    id: Uuid,
+}
+
+impl Reader {
+    pub fn path(id: &Uuid) -> String {
+        Self { id: *id }.to_string()
+    }
+}
+
 pub async fn index(_: Collection) -> Redirect {
     Redirect::to(&super::streams::Member::path("unread"))
 }
@@ -94,6 +108,14 @@ pub struct DropForm {
     url: String,
     tags: HashSet<String>,
 
+    // Only on the edit form. Highlights go one per line.
+    notes: String,
+    highlights: String,
+
+    // If this page was already saved, move it back to Unread.
+    #[serde(deserialize_with = "super::checkbox")]
+    requeue: bool,
+
     authenticity_token: String,
     errors: Option<Vec<String>>,
 }
@@ -115,6 +137,19 @@ impl DropForm {
             Err(errors)
         }
     }
+
+    fn notes(&self) -> Option<String> {
+        Some(self.notes.trim().to_string()).filter(present)
+    }
+
+    fn highlights(&self) -> Vec<String> {
+        self.highlights
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
 }
 
 impl From<firehose::Drop> for DropForm {
@@ -123,6 +158,8 @@ impl From<firehose::Drop> for DropForm {
             title: drop.drop.title.unwrap_or_default(),
             url: drop.drop.url,
             tags: drop.tags.iter().map(|t| t.id.to_string()).collect(),
+            notes: drop.drop.notes.unwrap_or_default(),
+            highlights: drop.drop.highlights.join("\n"),
 
             ..Default::default()
         }
@@ -184,6 +221,7 @@ impl ShareQuery {
         DropForm {
             title: title.to_string(),
             url: url.to_string(),
+            requeue: true,
             ..Default::default()
         }
     }
@@ -241,8 +279,8 @@ pub async fn create(
 
     let conn = db.acquire().await?;
 
-    let drop = firehose::create_drop(
-        conn,
+    let drop = firehose::save_drop(
+        &mut *conn,
         &session.user,
         title,
         form.url.clone(),
@@ -252,6 +290,32 @@ pub async fn create(
     )
     .await;
 
+    let drop = match drop {
+        Ok(firehose::SavedDrop::New(drop)) => {
+            // Pages can be slow, so whatever the drop is missing gets filled in later.
+            let task = jobs::FetchDropMetadata {
+                drop_id: drop.drop.id,
+            };
+            if let Err(err) = queue::push(&mut *conn, &task, now).await {
+                tracing::error!({ ?err }, "could not queue drop metadata fetch");
+            }
+            if let Err(err) = queue_article(&mut *conn, drop.drop.id, now).await {
+                tracing::error!({ ?err }, "could not queue drop article extraction");
+            }
+
+            Ok(drop)
+        }
+        Ok(firehose::SavedDrop::Merged(drop))
+            if form.requeue && drop.drop.status != DropStatus::Unread =>
+        {
+            firehose::move_drop(&mut *conn, drop, DropStatus::Unread, now)
+                .await
+                .map_err(anyhow::Error::from)
+        }
+        Ok(firehose::SavedDrop::Merged(drop)) => Ok(drop),
+        Err(err) => Err(err),
+    };
+
     match drop {
         Ok(drop) => Ok(Redirect::to(&Member::path(&drop.drop.id)).into_response()),
         Err(err) => {
@@ -339,6 +403,8 @@ pub async fn update(
     let fields = firehose::DropFields {
         title: Some(form.title.clone()).filter(present),
         url: Some(form.url.clone()).filter(present),
+        notes: Some(form.notes()),
+        highlights: Some(form.highlights()),
     };
     let tags = tag_selectors(&form.tags);
 
@@ -384,12 +450,87 @@ pub async fn r#move(
     let drop = firehose::find_drop(&mut db, &session.user, id).await?;
     let drop = firehose::move_drop(&mut db, drop, form.status, now).await?;
 
+    // Saved drops should stay readable, even if their pages go away.
+    if drop.drop.status == DropStatus::Saved
+        && firehose::find_drop_article(&mut db, &drop.drop)
+            .await?
+            .is_none()
+    {
+        queue_article(&mut db, drop.drop.id, now).await?;
+    }
+
     // Redirect back to the page the action was taken from. If we don't know, go to the
     // drop page.
     let dest = return_path.unwrap_or_else(|| Member::path(&drop.drop.id));
     Ok(Redirect::to(&dest))
 }
 
+#[derive(Template)]
+#[template(path = "firehose/drops/reader.html")]
+struct ReaderPage {
+    context: Context,
+    user: Option<User>,
+    drop: firehose::Drop,
+    article: Option<DropArticle>,
+
+    // Whether the article is waiting to be (re)fetched.
+    pending: bool,
+}
+
+pub async fn reader(
+    Reader { id }: Reader,
+    context: Context,
+    session: Session,
+    PgConn(mut db): PgConn,
+) -> super::Result<impl IntoResponse> {
+    let drop = firehose::find_drop(&mut db, &session.user, id).await?;
+    let article = firehose::find_drop_article(&mut db, &drop.drop).await?;
+
+    let task = jobs::ExtractDropArticle { drop_id: id };
+    let pending = queue::find_pending(&mut db, &task).await?.is_some();
+
+    Ok(ReaderPage {
+        context,
+        user: Some(session.user),
+        drop,
+        article,
+        pending,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ExtractForm {
+    authenticity_token: String,
+}
+
+pub async fn extract(
+    Reader { id }: Reader,
+    context: Context,
+    session: Session,
+    PgConn(mut db): PgConn,
+    Form(form): Form<ExtractForm>,
+) -> super::Result<impl IntoResponse> {
+    context.verify_csrf(&form.authenticity_token)?;
+
+    let drop = firehose::find_drop(&mut db, &session.user, id).await?;
+    queue_article(&mut db, drop.drop.id, chrono::Utc::now()).await?;
+
+    Ok(Redirect::to(&Reader::path(&id)))
+}
+
+// Fetch the drop's article in the background, unless that's already going to happen.
+async fn queue_article(
+    conn: &mut PgConnection,
+    drop_id: Uuid,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<()> {
+    let task = jobs::ExtractDropArticle { drop_id };
+    if queue::find_pending(&mut *conn, &task).await?.is_none() {
+        queue::push(&mut *conn, &task, now).await?;
+    }
+    Ok(())
+}
+
 fn bookmarklet(base_url: url::Url) -> String {
     let href = base_url.join(&New.to_string()).unwrap();
 
@@ -1,15 +1,21 @@
 use std::collections::HashSet;
 
 use askama::Template;
+use axum::body::Bytes;
+use axum::extract::{Multipart, Query, State};
 use axum::response::{IntoResponse, Redirect};
 use axum::Router;
 use axum_extra::routing::RouterExt;
 use axum_extra::{extract::Form, routing::TypedPath};
-use serde::{Deserialize, Deserializer};
+use http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use sqlx::PgConnection;
 use uuid::Uuid;
 
-use crate::firehose;
-use crate::models::User;
+use super::{PageQuery, Pagination, SectionQuery};
+use crate::firehose::DropStatus;
+use crate::models::{self, User};
+use crate::{feeds, firehose, http_client, jobs, opml, queue, websub};
 use crate::{
     filters,
     view_models::{tag_options, TagOption},
@@ -24,7 +30,14 @@ pub fn router() -> Router<AppState> {
         .typed_get(show)
         .typed_get(edit)
         .typed_post(update)
+        .typed_get(confirm_delete)
         .typed_post(delete)
+        .typed_post(fetch)
+        .typed_get(export)
+        .typed_get(import)
+        .typed_post(import_upload)
+        .typed_get(websub_verify)
+        .typed_post(websub_push)
 }
 
 #[derive(TypedPath, Deserialize)]
@@ -93,27 +106,36 @@ pub async fn index(
 pub struct HydrantForm {
     name: String,
     url: String,
-    #[serde(deserialize_with = "checkbox")]
+    source_kind: models::HydrantSourceKind,
+    #[serde(deserialize_with = "super::checkbox")]
     active: bool,
+    fetch_interval_minutes: String,
+    include_rules: String,
+    exclude_rules: String,
+    first_fetch_limit: String,
+    max_drops_per_fetch: String,
+    #[serde(deserialize_with = "super::checkbox")]
+    backfill_read: bool,
     tags: HashSet<String>,
+    preview: Option<String>,
+    choice: Option<String>,
+
+    #[serde(skip)]
+    feed_choices: Vec<feeds::Link>,
 
     authenticity_token: String,
     errors: Option<Vec<String>>,
 }
 
-fn checkbox<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    match String::deserialize(deserializer)?.as_ref() {
-        "on" => Ok(true),
-        _ => Ok(false),
-    }
-}
-
 // TODO: I bet this can be derived
 impl HydrantForm {
-    fn validate(&self) -> Result<(), Vec<String>> {
+    // Check the form, and if it's active, that the URL can be read as its kind of source. The feed
+    // comes back when it was checked so it can be previewed. If the URL was a page that links to
+    // more than one feed, those become choices for the next try.
+    async fn validate(
+        &mut self,
+        client: &http_client::Client,
+    ) -> Result<Option<firehose::FoundFeed>, Vec<String>> {
         if let Some(errors) = &self.errors {
             return Err(errors.to_vec());
         }
@@ -125,13 +147,80 @@ impl HydrantForm {
         if self.url.is_empty() {
             errors.push("URL cannot be blank".to_string());
         }
+        if self.fetch_interval().is_none() {
+            errors.push("Fetch interval must be a positive number of minutes".to_string());
+        }
+        if super::parse_limit(&self.first_fetch_limit).is_none() {
+            errors.push("First fetch limit must be blank or a positive number".to_string());
+        }
+        if super::parse_limit(&self.max_drops_per_fetch).is_none() {
+            errors.push("Drops per fetch must be blank or a positive number".to_string());
+        }
+        let rules = self.rules();
+        for rule in rules.include.iter().chain(rules.exclude.iter()) {
+            if let Err(err) = firehose::FilterRule::parse(rule) {
+                errors.push(format!("Invalid rule {}: {}", rule, err));
+            }
+        }
+
+        // Inactive hydrants aren't fetched, so a broken feed shouldn't stop anyone from turning
+        // one off.
+        let mut feed = None;
+        if !self.url.is_empty() && self.active {
+            match firehose::preview_hydrant(client, self.source_kind, &self.url).await {
+                Ok(f) => feed = Some(f),
+                Err(firehose::FeedError::Ambiguous(links)) => {
+                    errors.push("This page links to more than one feed. Pick one.".to_string());
+                    self.feed_choices = links;
+                }
+                Err(err) => errors.push(format!("URL is not usable: {}", err)),
+            }
+        }
 
         if errors.is_empty() {
-            Ok(())
+            Ok(feed)
         } else {
             Err(errors)
         }
     }
+
+    // Picking one of several discovered feeds replaces the URL that led to them.
+    fn choose_feed(&mut self) {
+        if let Some(choice) = self.choice.take() {
+            self.url = choice;
+        }
+    }
+
+    fn rules(&self) -> firehose::FilterRules {
+        fn lines(s: &str) -> Vec<String> {
+            s.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+
+        firehose::FilterRules {
+            include: lines(&self.include_rules),
+            exclude: lines(&self.exclude_rules),
+        }
+    }
+
+    fn fetch_interval(&self) -> Option<i32> {
+        self.fetch_interval_minutes
+            .trim()
+            .parse()
+            .ok()
+            .filter(|minutes| *minutes > 0)
+    }
+
+    fn policy(&self) -> firehose::IngestionPolicy {
+        firehose::IngestionPolicy {
+            first_fetch_limit: super::parse_limit(&self.first_fetch_limit).unwrap_or_default(),
+            max_drops_per_fetch: super::parse_limit(&self.max_drops_per_fetch).unwrap_or_default(),
+            backfill_read: self.backfill_read,
+        }
+    }
 }
 
 impl From<firehose::Hydrant> for HydrantForm {
@@ -142,7 +231,14 @@ impl From<firehose::Hydrant> for HydrantForm {
             errors: None,
             name: hydrant.hydrant.name,
             url: hydrant.hydrant.url,
+            source_kind: hydrant.hydrant.source_kind,
             active: hydrant.hydrant.active,
+            fetch_interval_minutes: hydrant.hydrant.fetch_interval_minutes.to_string(),
+            include_rules: hydrant.hydrant.include_rules.join("\n"),
+            exclude_rules: hydrant.hydrant.exclude_rules.join("\n"),
+            first_fetch_limit: super::format_limit(hydrant.hydrant.first_fetch_limit),
+            max_drops_per_fetch: super::format_limit(hydrant.hydrant.max_drops_per_fetch),
+            backfill_read: hydrant.hydrant.backfill_read,
             tags,
 
             ..Default::default()
@@ -157,6 +253,38 @@ struct NewHydrant {
     user: Option<User>,
     hydrant: HydrantForm,
     tag_options: Vec<TagOption>,
+    preview: Option<FeedPreview>,
+}
+
+const PREVIEW_ITEMS: usize = 10;
+
+struct FeedPreview {
+    title: Option<String>,
+    item_count: usize,
+    items: Vec<feeds::Entry>,
+}
+
+impl FeedPreview {
+    fn item_count(&self) -> usize {
+        self.item_count
+    }
+}
+
+impl From<feeds::Feed> for FeedPreview {
+    fn from(feed: feeds::Feed) -> Self {
+        let item_count = feed.entries.len();
+
+        // Show the newest items first. Undated ones sort last, but otherwise stay in feed order.
+        let mut items = feed.entries;
+        items.sort_by_key(|item| std::cmp::Reverse(item.published_at));
+        items.truncate(PREVIEW_ITEMS);
+
+        Self {
+            title: feed.title,
+            item_count,
+            items,
+        }
+    }
 }
 
 pub async fn new(
@@ -172,9 +300,12 @@ pub async fn new(
         user: Some(session.user),
         hydrant: HydrantForm {
             active: true,
+            fetch_interval_minutes: models::Hydrant::DEFAULT_FETCH_INTERVAL_MINUTES.to_string(),
+            first_fetch_limit: models::Hydrant::DEFAULT_FIRST_FETCH_LIMIT.to_string(),
             ..Default::default()
         },
         tag_options: tag_options(tags),
+        preview: None,
     })
 }
 
@@ -182,19 +313,55 @@ pub async fn create(
     _: Collection,
     context: Context,
     session: Session,
+    State(client): State<http_client::Client>,
     PgConn(mut db): PgConn,
     Form(mut form): Form<HydrantForm>,
 ) -> super::Result<impl IntoResponse> {
     context.verify_csrf(&form.authenticity_token)?;
-    form.errors = form.validate().err();
+
+    form.choose_feed();
+
+    let feed = match form.validate(&client).await {
+        Ok(feed) => feed,
+        Err(errors) => {
+            form.errors = Some(errors);
+            None
+        }
+    };
+
+    // Save the feed itself even if the URL was for a page that links to it.
+    if let Some(found) = &feed {
+        form.url = found.url.clone();
+    }
+
+    // Show the form again, with what the feed looks like if it worked, either because there were
+    // errors or because the preview was all that was asked for.
+    if form.errors.is_some() || form.preview.is_some() {
+        let tags = firehose::list_tags(&mut db, &session.user).await?;
+
+        return Ok(NewHydrant {
+            context,
+            user: Some(session.user),
+            hydrant: form,
+            tag_options: tag_options(tags),
+            preview: feed.map(|found| found.feed.into()),
+        }
+        .into_response());
+    }
 
     let hydrant = firehose::create_hydrant(
         &mut db,
         &session.user,
-        &form.name,
-        &form.url,
-        form.active,
-        Some(tag_selectors(&form.tags)),
+        firehose::NewHydrant {
+            name: form.name.clone(),
+            url: form.url.clone(),
+            source_kind: form.source_kind,
+            active: form.active,
+            fetch_interval_minutes: form.fetch_interval().unwrap_or_default(),
+            rules: form.rules(),
+            policy: form.policy(),
+            tags: Some(tag_selectors(&form.tags)),
+        },
     )
     .await;
 
@@ -202,6 +369,7 @@ pub async fn create(
         Ok(hydrant) => Ok(Redirect::to(&Member::path(&hydrant.hydrant.id)).into_response()),
         Err(err) => {
             tracing::error!({ ?err }, "could not create hydrant");
+            form.errors = Some(vec!["Could not save the hydrant. Try again?".to_string()]);
 
             let tags = firehose::list_tags(&mut db, &session.user).await?;
 
@@ -210,6 +378,7 @@ pub async fn create(
                 user: Some(session.user),
                 hydrant: form,
                 tag_options: tag_options(tags),
+                preview: None,
             }
             .into_response())
         }
@@ -222,6 +391,14 @@ struct Show {
     context: Context,
     user: Option<User>,
     hydrant: firehose::Hydrant,
+    subscription: Option<models::HydrantSubscription>,
+    events: Vec<models::HydrantEvent>,
+    fetches: Vec<models::HydrantFetch>,
+    job: Option<queue::Job>,
+
+    unread: HydrantSection,
+    read: HydrantSection,
+    saved: HydrantSection,
 }
 
 pub async fn show(
@@ -229,18 +406,101 @@ pub async fn show(
     context: Context,
     session: Session,
     PgConn(mut db): PgConn,
+    Query(section): Query<SectionQuery>,
+    Query(page): Query<PageQuery>,
 ) -> super::Result<impl IntoResponse> {
     let id = parse_hydrant_id(&id)?;
     let hydrant = firehose::find_hydrant(&mut db, &session.user, id).await?;
 
     // TODO: map_err(404)?
 
-    // TODO: show hydrant drops?
+    let subscription = firehose::find_subscription(&mut *db, hydrant.hydrant.id).await?;
+    let events = firehose::list_hydrant_events(&mut db, &hydrant.hydrant, 20).await?;
+    let fetches = firehose::list_hydrant_fetches(&mut db, &hydrant.hydrant, 20).await?;
+
+    let task = jobs::HydrateOne {
+        hydrant_id: hydrant.hydrant.id,
+    };
+    let job = queue::find_pending(&mut db, &task).await?;
+
+    let cursor = |status: DropStatus| page.cursor().filter(|_| section.status == Some(status));
+
+    let unread = load_hydrant_section(
+        &mut db,
+        &session.user,
+        hydrant.hydrant.id,
+        DropStatus::Unread,
+        cursor(DropStatus::Unread),
+        8,
+    )
+    .await?;
+    let read = load_hydrant_section(
+        &mut db,
+        &session.user,
+        hydrant.hydrant.id,
+        DropStatus::Read,
+        cursor(DropStatus::Read),
+        DROPS_PER_PAGE,
+    )
+    .await?;
+    let saved = load_hydrant_section(
+        &mut db,
+        &session.user,
+        hydrant.hydrant.id,
+        DropStatus::Saved,
+        cursor(DropStatus::Saved),
+        DROPS_PER_PAGE,
+    )
+    .await?;
 
     Ok(Show {
         context,
         user: Some(session.user),
         hydrant,
+        subscription,
+        events,
+        fetches,
+        job,
+        unread,
+        read,
+        saved,
+    })
+}
+
+const DROPS_PER_PAGE: i64 = 32;
+
+struct HydrantSection {
+    drops: Vec<firehose::Drop>,
+    pagination: Pagination,
+}
+
+async fn load_hydrant_section(
+    conn: &mut PgConnection,
+    user: &User,
+    hydrant_id: Uuid,
+    status: DropStatus,
+    cursor: Option<firehose::PageCursor>,
+    size: i64,
+) -> anyhow::Result<HydrantSection> {
+    let page = firehose::list_drops_page(
+        &mut *conn,
+        user,
+        firehose::DropFilters {
+            hydrant_id: Some(hydrant_id),
+            status: Some(status),
+            ..Default::default()
+        },
+        cursor,
+        size,
+    )
+    .await?;
+
+    let status = status.to_string();
+    let pagination = Pagination::new(&Member::path(&hydrant_id), &[("status", &status)], &page);
+
+    Ok(HydrantSection {
+        drops: page.drops,
+        pagination,
     })
 }
 
@@ -279,21 +539,46 @@ pub async fn update(
     Member { id }: Member,
     context: Context,
     session: Session,
+    State(client): State<http_client::Client>,
     PgConn(mut db): PgConn,
     Form(mut form): Form<HydrantForm>,
 ) -> super::Result<impl IntoResponse> {
     context.verify_csrf(&form.authenticity_token)?;
-    form.errors = form.validate().err();
+
+    form.choose_feed();
+
+    match form.validate(&client).await {
+        Ok(Some(found)) => form.url = found.url,
+        Ok(None) => (),
+        Err(errors) => form.errors = Some(errors),
+    }
 
     let id = parse_hydrant_id(&id)?;
     let hydrant = firehose::find_hydrant(&mut db, &session.user, id).await?;
 
+    if form.errors.is_some() {
+        let tags = firehose::list_tags(&mut db, &session.user).await?;
+
+        return Ok(EditHydrant {
+            context,
+            user: Some(session.user),
+            id,
+            hydrant: form,
+            tag_options: tag_options(tags),
+        }
+        .into_response());
+    }
+
     let tags = tag_selectors(&form.tags);
 
     let fields = firehose::HydrantFields {
         name: Some(form.name.clone()),
         url: Some(form.url.clone()),
+        source_kind: Some(form.source_kind),
         active: Some(form.active),
+        fetch_interval_minutes: Some(form.fetch_interval().unwrap_or_default()),
+        rules: Some(form.rules()),
+        policy: Some(form.policy()),
         tags: Some(tags),
     };
 
@@ -301,6 +586,7 @@ pub async fn update(
         Ok(hydrant) => Ok(Redirect::to(&Member::path(&hydrant.hydrant.id)).into_response()),
         Err(err) => {
             tracing::error!({ ?err }, "could not update hydrant");
+            form.errors = Some(vec!["Could not save the hydrant. Try again?".to_string()]);
 
             let tags = firehose::list_tags(&mut db, &session.user).await?;
 
@@ -328,9 +614,38 @@ impl Delete {
     }
 }
 
+#[derive(Template)]
+#[template(path = "firehose/hydrants/delete.html")]
+struct ConfirmDelete {
+    context: Context,
+    user: Option<User>,
+    hydrant: firehose::Hydrant,
+    counts: firehose::DropCounts,
+}
+
+// Deleting a hydrant means deciding what happens to its drops, so that gets its own page.
+pub async fn confirm_delete(
+    Delete { id }: Delete,
+    context: Context,
+    session: Session,
+    PgConn(mut db): PgConn,
+) -> super::Result<impl IntoResponse> {
+    let hydrant = firehose::find_hydrant(&mut db, &session.user, id).await?;
+    let counts = firehose::count_hydrant_drops(&mut db, &hydrant.hydrant).await?;
+
+    Ok(ConfirmDelete {
+        context,
+        user: Some(session.user),
+        hydrant,
+        counts,
+    })
+}
+
 #[derive(Deserialize)]
 pub struct HydrantDeleteForm {
     authenticity_token: String,
+    #[serde(default)]
+    drops: firehose::DropDisposal,
 }
 
 pub async fn delete(
@@ -344,11 +659,148 @@ pub async fn delete(
 
     let hydrant = firehose::find_hydrant(&mut db, &session.user, id).await?;
 
-    firehose::delete_hydrant(&mut db, &session.user, hydrant.hydrant).await?;
+    firehose::delete_hydrant(&mut db, &session.user, hydrant.hydrant, form.drops).await?;
 
     Ok(Redirect::to(&Collection.to_string()))
 }
 
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/hydrants/:id/fetch")]
+pub struct Fetch {
+    id: Uuid,
+}
+
+impl Fetch {
+    pub fn path(id: &Uuid) -> String {
+        Self { id: *id }.to_string()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HydrantFetchForm {
+    authenticity_token: String,
+}
+
+pub async fn fetch(
+    Fetch { id }: Fetch,
+    context: Context,
+    session: Session,
+    PgConn(mut db): PgConn,
+    Form(form): Form<HydrantFetchForm>,
+) -> super::Result<impl IntoResponse> {
+    context.verify_csrf(&form.authenticity_token)?;
+
+    let hydrant = firehose::find_hydrant(&mut db, &session.user, id).await?;
+
+    // Don't pile up fetches if the button gets clicked a few times.
+    let task = jobs::HydrateOne {
+        hydrant_id: hydrant.hydrant.id,
+    };
+    if queue::find_pending(&mut db, &task).await?.is_none() {
+        queue::push(&mut db, &task, chrono::Utc::now()).await?;
+    }
+
+    Ok(Redirect::to(&Member::path(&hydrant.hydrant.id)))
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/hydrants/export")]
+pub struct Export;
+
+pub async fn export(
+    _: Export,
+    session: Session,
+    PgConn(mut db): PgConn,
+) -> super::Result<impl IntoResponse> {
+    let hydrants = firehose::list_hydrants(&mut db, &session.user).await?;
+    let content = firehose::export_opml(&hydrants).map_err(super::Error::boxed)?;
+
+    let headers = [
+        (http::header::CONTENT_TYPE, "text/x-opml; charset=utf-8"),
+        (
+            http::header::CONTENT_DISPOSITION,
+            r#"attachment; filename="hydrants.opml""#,
+        ),
+    ];
+    Ok((headers, content))
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/hydrants/import")]
+pub struct Import;
+
+#[derive(Template)]
+#[template(path = "firehose/hydrants/import.html")]
+struct ImportHydrants {
+    context: Context,
+    user: Option<User>,
+    errors: Option<Vec<String>>,
+    outcomes: Vec<firehose::ImportOutcome>,
+}
+
+pub async fn import(_: Import, context: Context, session: Session) -> impl IntoResponse {
+    ImportHydrants {
+        context,
+        user: Some(session.user),
+        errors: None,
+        outcomes: Vec::new(),
+    }
+}
+
+pub async fn import_upload(
+    _: Import,
+    context: Context,
+    session: Session,
+    PgConn(mut db): PgConn,
+    mut multipart: Multipart,
+) -> super::Result<impl IntoResponse> {
+    let mut authenticity_token = String::new();
+    let mut content = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(super::Error::boxed)? {
+        match field.name() {
+            Some("authenticity_token") => {
+                authenticity_token = field.text().await.map_err(super::Error::boxed)?;
+            }
+            Some("file") => {
+                content = Some(field.bytes().await.map_err(super::Error::boxed)?);
+            }
+            _ => (),
+        }
+    }
+
+    context.verify_csrf(&authenticity_token)?;
+
+    let outlines = match content.as_deref().map(opml::parse) {
+        Some(Ok(outlines)) => outlines,
+        Some(Err(err)) => {
+            return Ok(ImportHydrants {
+                context,
+                user: Some(session.user),
+                errors: Some(vec![format!("Could not read the file: {}", err)]),
+                outcomes: Vec::new(),
+            });
+        }
+        None => {
+            return Ok(ImportHydrants {
+                context,
+                user: Some(session.user),
+                errors: Some(vec!["Choose an OPML file to import".to_string()]),
+                outcomes: Vec::new(),
+            });
+        }
+    };
+
+    let outcomes = firehose::import_opml(&mut db, &session.user, outlines).await?;
+
+    Ok(ImportHydrants {
+        context,
+        user: Some(session.user),
+        errors: None,
+        outcomes,
+    })
+}
+
 // TODO: Third copy, extract it.
 fn tag_selectors(opts: &HashSet<String>) -> Vec<firehose::TagSelector> {
     opts.iter()
@@ -375,3 +827,49 @@ fn parse_hydrant_id(id: &str) -> super::Result<Uuid> {
         hydrant_id: id.to_string(),
     })
 }
+
+// The callback URL that WebSub hubs confirm subscriptions with and push new content to. Hubs
+// aren't logged in, so the hydrant is found by ID alone and pushes are checked by signature.
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/hydrants/:id/websub")]
+pub struct WebSub {
+    id: Uuid,
+}
+
+impl WebSub {
+    pub fn path(id: &Uuid) -> String {
+        Self { id: *id }.to_string()
+    }
+}
+
+pub async fn websub_verify(
+    WebSub { id }: WebSub,
+    PgConn(mut db): PgConn,
+    Query(verification): Query<websub::Verification>,
+) -> super::Result<impl IntoResponse> {
+    let now = chrono::Utc::now();
+
+    if firehose::verify_subscription(&mut db, id, &verification, now).await? {
+        Ok((StatusCode::OK, verification.challenge.unwrap_or_default()))
+    } else {
+        Ok((StatusCode::NOT_FOUND, String::new()))
+    }
+}
+
+pub async fn websub_push(
+    WebSub { id }: WebSub,
+    PgConn(mut db): PgConn,
+    headers: HeaderMap,
+    body: Bytes,
+) -> super::Result<impl IntoResponse> {
+    let now = chrono::Utc::now();
+
+    let signature = headers
+        .get("x-hub-signature")
+        .and_then(|value| value.to_str().ok());
+
+    match firehose::Hydrant::receive(&mut db, id, signature, &body, now).await? {
+        firehose::PushOutcome::NotSubscribed => Ok(StatusCode::NOT_FOUND),
+        _ => Ok(StatusCode::ACCEPTED),
+    }
+}
@@ -5,6 +5,7 @@ use axum::{
     Router,
 };
 use http::{header, HeaderValue, StatusCode};
+use serde::{Deserialize, Deserializer};
 
 use crate::{auth::Session, AppState, Context, User};
 
@@ -13,6 +14,7 @@ pub mod drops;
 pub mod firehose;
 pub mod home;
 pub mod hydrants;
+pub mod search;
 pub mod streams;
 pub mod tags;
 pub mod whoops;
@@ -24,6 +26,7 @@ pub fn router(state: AppState) -> Router<AppState> {
         .merge(firehose::router())
         .merge(home::router())
         .merge(hydrants::router())
+        .merge(search::router())
         .merge(streams::router())
         .merge(tags::router())
         .merge(whoops::router())
@@ -179,4 +182,88 @@ fn resource_not_found(context: Context, user: Option<User>, resource: &str) -> R
     (StatusCode::NOT_FOUND, body).into_response()
 }
 
+// Checkboxes only send a value when they're checked.
+fn checkbox<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match String::deserialize(deserializer)?.as_ref() {
+        "on" => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+// Limits are optional, so a blank one is valid too. It just means there isn't one.
+fn parse_limit(s: &str) -> Option<Option<i32>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Some(None);
+    }
+
+    s.parse().ok().filter(|limit| *limit > 0).map(Some)
+}
+
+fn format_limit(limit: Option<i32>) -> String {
+    limit.map(|limit| limit.to_string()).unwrap_or_default()
+}
+
+// Which page of drops to show, from the query string.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PageQuery {
+    after: Option<crate::firehose::Cursor>,
+    before: Option<crate::firehose::Cursor>,
+}
+
+impl PageQuery {
+    pub fn cursor(&self) -> Option<crate::firehose::PageCursor> {
+        use crate::firehose::PageCursor;
+
+        match (self.after, self.before) {
+            (Some(cursor), _) => Some(PageCursor::After(cursor)),
+            (None, Some(cursor)) => Some(PageCursor::Before(cursor)),
+            (None, None) => None,
+        }
+    }
+}
+
+// Pages of drops that are split up by status give each status its own pages, so the query string
+// says which one is being paged through.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SectionQuery {
+    status: Option<crate::firehose::DropStatus>,
+}
+
+// Links to the pages around a page of drops. The params are for anything else in the query string
+// that the neighboring pages should keep.
+pub struct Pagination {
+    pub prev: Option<String>,
+    pub next: Option<String>,
+}
+
+impl Pagination {
+    pub fn new(path: &str, params: &[(&str, &str)], page: &crate::firehose::DropPage) -> Self {
+        let href = |cursor: &crate::firehose::PageCursor| {
+            use crate::firehose::PageCursor;
+
+            let (key, cursor) = match cursor {
+                PageCursor::After(cursor) => ("after", cursor),
+                PageCursor::Before(cursor) => ("before", cursor),
+            };
+
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params)
+                .append_pair(key, &cursor.to_string())
+                .finish();
+            format!("{}?{}", path, query)
+        };
+
+        Self {
+            prev: page.prev.as_ref().map(href),
+            next: page.next.as_ref().map(href),
+        }
+    }
+}
+
 // TODO: Web prelude = {Result, Error, StdResult}
@@ -0,0 +1,197 @@
+use askama::Template;
+use axum::{extract::Query, response::IntoResponse, Router};
+use axum_extra::routing::{RouterExt, TypedPath};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{PageQuery, Pagination};
+use crate::filters;
+use crate::firehose::{self, DropStatus};
+use crate::models::{Tag, User};
+use crate::{AppState, Context, PgConn, Session};
+
+const DROPS_PER_PAGE: i64 = 32;
+
+pub fn router() -> Router<AppState> {
+    Router::new().typed_get(show)
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/firehose/search")]
+pub struct Search;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    q: String,
+    status: Option<DropStatus>,
+    tag: Option<Uuid>,
+}
+
+impl SearchQuery {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("q", self.q.clone())];
+        if let Some(status) = self.status {
+            params.push(("status", status.to_string()));
+        }
+        if let Some(tag) = self.tag {
+            params.push(("tag", tag.to_string()));
+        }
+        params
+    }
+
+    fn path(&self) -> String {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.params())
+            .finish();
+
+        format!("{}?{}", Search, query)
+    }
+
+    // The same search, narrowed to a status (or widened to all of them).
+    fn with_status(&self, status: &Option<DropStatus>) -> String {
+        Self {
+            status: *status,
+            ..self.clone()
+        }
+        .path()
+    }
+
+    // The same search, narrowed to a tag (or widened to all of them).
+    fn with_tag(&self, tag: &Option<Uuid>) -> String {
+        Self {
+            tag: *tag,
+            ..self.clone()
+        }
+        .path()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "firehose/search.html")]
+struct Show {
+    context: Context,
+    user: Option<User>,
+    search: SearchQuery,
+    drops: Vec<firehose::Drop>,
+    pagination: Pagination,
+    counts: firehose::DropCounts,
+    tag_counts: Vec<firehose::TagCount>,
+}
+
+// A link that narrows (or widens) the current search.
+struct Facet {
+    label: String,
+    count: Option<i64>,
+    href: String,
+    selected: bool,
+}
+
+impl Show {
+    fn status_facets(&self) -> Vec<Facet> {
+        let all = Facet {
+            label: "All".to_string(),
+            count: Some(self.counts.total()),
+            href: self.search.with_status(&None),
+            selected: self.search.status.is_none(),
+        };
+
+        let statuses = [
+            (DropStatus::Unread, "Unread", self.counts.unread),
+            (DropStatus::Read, "Read", self.counts.read),
+            (DropStatus::Saved, "Saved", self.counts.saved),
+        ];
+
+        std::iter::once(all)
+            .chain(statuses.into_iter().map(|(status, label, count)| Facet {
+                label: label.to_string(),
+                count: Some(count),
+                href: self.search.with_status(&Some(status)),
+                selected: self.search.status == Some(status),
+            }))
+            .collect()
+    }
+
+    fn tag_facets(&self) -> Vec<Facet> {
+        let all = Facet {
+            label: "Any".to_string(),
+            count: None,
+            href: self.search.with_tag(&None),
+            selected: self.search.tag.is_none(),
+        };
+
+        std::iter::once(all)
+            .chain(self.tag_counts.iter().map(|tag_count| Facet {
+                label: tag_count.tag.name.clone(),
+                count: Some(tag_count.drops),
+                href: self.search.with_tag(&Some(tag_count.tag.id)),
+                selected: self.search.tag == Some(tag_count.tag.id),
+            }))
+            .collect()
+    }
+}
+
+pub async fn show(
+    _: Search,
+    context: Context,
+    session: Session,
+    PgConn(mut conn): PgConn,
+    Query(search): Query<SearchQuery>,
+    Query(page): Query<PageQuery>,
+) -> super::Result<impl IntoResponse> {
+    let terms = search.q.trim();
+
+    // Without any terms, there's nothing to search for yet.
+    if terms.is_empty() {
+        return Ok(Show {
+            context,
+            user: Some(session.user),
+            search,
+            drops: Vec::new(),
+            pagination: Pagination {
+                prev: None,
+                next: None,
+            },
+            counts: Default::default(),
+            tag_counts: Vec::new(),
+        });
+    }
+
+    // Tags that belong to someone else don't match anything.
+    let tags: Option<Vec<Tag>> = match search.tag {
+        Some(id) => Some(firehose::find_tags(&mut conn, &session.user, &[id]).await?),
+        None => None,
+    };
+
+    let filters = firehose::DropFilters {
+        status: search.status,
+        tags,
+        query: Some(terms.to_string()),
+        ..Default::default()
+    };
+
+    let page = firehose::list_drops_page(
+        &mut conn,
+        &session.user,
+        filters.clone(),
+        page.cursor(),
+        DROPS_PER_PAGE,
+    )
+    .await?;
+    let params = search.params();
+    let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let pagination = Pagination::new(&Search.to_string(), &params, &page);
+
+    let counts = firehose::count_drops(&mut conn, &session.user, filters.clone()).await?;
+    let tag_counts = firehose::count_drop_tags(&mut conn, &session.user, filters).await?;
+
+    Ok(Show {
+        context,
+        user: Some(session.user),
+        search,
+        drops: page.drops,
+        pagination,
+        counts,
+        tag_counts,
+    })
+}
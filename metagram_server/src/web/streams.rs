@@ -1,4 +1,5 @@
 use askama::Template;
+use axum::extract::Query;
 use axum::response::{IntoResponse, Redirect};
 use axum::Router;
 use axum_extra::routing::RouterExt;
@@ -6,6 +7,7 @@ use axum_extra::{extract::Form, routing::TypedPath};
 use serde::Deserialize;
 use uuid::Uuid;
 
+use super::{PageQuery, Pagination};
 use crate::filters;
 use crate::firehose;
 use crate::models::{DropStatus, User};
@@ -95,6 +97,7 @@ struct NewStream {
 pub struct StreamForm {
     name: String,
     tags: Vec<String>,
+    max_reading_minutes: String,
 
     authenticity_token: String,
     errors: Option<Vec<String>>,
@@ -110,6 +113,9 @@ impl StreamForm {
         if self.name.is_empty() {
             errors.push("Name cannot be blank".to_string());
         }
+        if super::parse_limit(&self.max_reading_minutes).is_none() {
+            errors.push("Reading time must be blank or a positive number of minutes".to_string());
+        }
 
         if errors.is_empty() {
             Ok(())
@@ -117,6 +123,10 @@ impl StreamForm {
             Err(errors)
         }
     }
+
+    fn max_reading_minutes(&self) -> Option<i32> {
+        super::parse_limit(&self.max_reading_minutes).unwrap_or_default()
+    }
 }
 
 impl From<firehose::CustomStream> for StreamForm {
@@ -129,6 +139,7 @@ impl From<firehose::CustomStream> for StreamForm {
                 .cloned()
                 .map(|t| t.id.to_string())
                 .collect(),
+            max_reading_minutes: super::format_limit(stream.stream.max_reading_minutes),
 
             ..Default::default()
         }
@@ -175,7 +186,9 @@ pub async fn create(
             errs.push("Error finding tags. Was one recently deleted?".to_string());
             Some(errs)
         };
+    }
 
+    if form.errors.is_some() {
         let tags = firehose::list_tags(&mut db, &session.user).await?;
 
         return Ok(NewStream {
@@ -189,7 +202,16 @@ pub async fn create(
 
     let tags = firehose::find_tags(&mut db, &session.user, &tag_ids).await?;
 
-    match firehose::create_stream(&mut db, &session.user, &form.name, &tags).await {
+    let max_reading_minutes = form.max_reading_minutes();
+    match firehose::create_stream(
+        &mut db,
+        &session.user,
+        &form.name,
+        &tags,
+        max_reading_minutes,
+    )
+    .await
+    {
         Ok(stream) => Ok(Redirect::to(
             &Member {
                 id: stream.stream.id.to_string(),
@@ -220,39 +242,32 @@ struct ShowPage {
     user: Option<User>,
     stream: firehose::Stream,
     drops: Vec<firehose::Drop>,
+    pagination: Pagination,
 }
 
-const DEFAULT_DROP_LIMIT: i64 = 32;
+const DROPS_PER_PAGE: i64 = 32;
 
 pub async fn show(
     Member { id }: Member,
     context: Context,
     session: Session,
     PgConn(mut db): PgConn,
+    Query(page): Query<PageQuery>,
 ) -> super::Result<impl IntoResponse> {
-    let (stream, limit): (firehose::Stream, Option<i64>) = match id.as_str() {
-        "unread" => Ok((
-            firehose::Stream::Status(firehose::StatusStream {
-                status: DropStatus::Unread,
-            }),
-            Some(DEFAULT_DROP_LIMIT),
-        )),
-        "read" => Ok((
-            firehose::Stream::Status(firehose::StatusStream {
-                status: DropStatus::Read,
-            }),
-            None,
-        )),
-        "saved" => Ok((
-            firehose::Stream::Status(firehose::StatusStream {
-                status: DropStatus::Saved,
-            }),
-            None,
-        )),
+    let stream: firehose::Stream = match id.as_str() {
+        "unread" => Ok(firehose::Stream::Status(firehose::StatusStream {
+            status: DropStatus::Unread,
+        })),
+        "read" => Ok(firehose::Stream::Status(firehose::StatusStream {
+            status: DropStatus::Read,
+        })),
+        "saved" => Ok(firehose::Stream::Status(firehose::StatusStream {
+            status: DropStatus::Saved,
+        })),
 
         id => firehose::find_stream(&mut db, &session.user, parse_stream_id(id)?)
             .await
-            .map(|c| (firehose::Stream::Custom(c), Some(DEFAULT_DROP_LIMIT))),
+            .map(firehose::Stream::Custom),
     }?;
 
     let mut filters = stream.filters();
@@ -262,13 +277,22 @@ pub async fn show(
         filters.status = Some(DropStatus::Unread);
     }
 
-    let drops = firehose::list_drops(&mut db, &session.user, filters, limit).await?;
+    let drops = firehose::list_drops_page(
+        &mut db,
+        &session.user,
+        filters,
+        page.cursor(),
+        DROPS_PER_PAGE,
+    )
+    .await?;
+    let pagination = Pagination::new(&Member::path(&id), &[], &drops);
 
     Ok(ShowPage {
         context,
         user: Some(session.user),
         stream,
-        drops,
+        drops: drops.drops,
+        pagination,
     })
 }
 
@@ -328,7 +352,9 @@ pub async fn update(
             errs.push("Error finding tags. Was one recently deleted?".to_string());
             Some(errs)
         };
+    }
 
+    if form.errors.is_some() {
         let tags = firehose::list_tags(&mut db, &session.user).await?;
 
         return Ok(EditStream {
@@ -346,6 +372,7 @@ pub async fn update(
     let fields = firehose::StreamFields {
         name: Some(form.name.clone()),
         tag_ids: Some(tags.iter().map(|t| t.id).collect()),
+        max_reading_minutes: Some(form.max_reading_minutes()),
     };
 
     let stream = firehose::update_stream(&mut db, &session.user, &stream.stream, fields).await;
@@ -1,6 +1,6 @@
 use askama::Template;
 use axum::{
-    extract::Form,
+    extract::{Form, Query},
     response::{IntoResponse, Redirect},
     Router,
 };
@@ -9,6 +9,7 @@ use serde::Deserialize;
 use sqlx::PgConnection;
 use uuid::Uuid;
 
+use super::{PageQuery, Pagination, SectionQuery};
 use crate::filters;
 use crate::firehose::{self, DropStatus};
 use crate::models::{Tag, User};
@@ -177,9 +178,9 @@ struct Show {
     user: Option<User>,
     tag: Tag,
 
-    unread_drops: Vec<firehose::Drop>,
-    read_drops: Vec<firehose::Drop>,
-    saved_drops: Vec<firehose::Drop>,
+    unread: TagSection,
+    read: TagSection,
+    saved: TagSection,
 }
 
 pub async fn show(
@@ -187,70 +188,86 @@ pub async fn show(
     context: Context,
     session: Session,
     PgConn(mut conn): PgConn,
+    Query(section): Query<SectionQuery>,
+    Query(page): Query<PageQuery>,
 ) -> super::Result<impl IntoResponse> {
     let id = parse_tag_id(&id)?;
     let tag = firehose::find_tag(&mut conn, &session.user, id).await?;
-    let drops = load_tag_drops(&mut conn, &session.user, tag.clone(), 8).await?;
+
+    let cursor = |status: DropStatus| page.cursor().filter(|_| section.status == Some(status));
+
+    let unread = load_tag_section(
+        &mut conn,
+        &session.user,
+        tag.clone(),
+        DropStatus::Unread,
+        cursor(DropStatus::Unread),
+        8,
+    )
+    .await?;
+    let read = load_tag_section(
+        &mut conn,
+        &session.user,
+        tag.clone(),
+        DropStatus::Read,
+        cursor(DropStatus::Read),
+        DROPS_PER_PAGE,
+    )
+    .await?;
+    let saved = load_tag_section(
+        &mut conn,
+        &session.user,
+        tag.clone(),
+        DropStatus::Saved,
+        cursor(DropStatus::Saved),
+        DROPS_PER_PAGE,
+    )
+    .await?;
 
     Ok(Show {
         context,
         user: Some(session.user),
         tag,
-        unread_drops: drops.unread_drops,
-        read_drops: drops.read_drops,
-        saved_drops: drops.saved_drops,
+        unread,
+        read,
+        saved,
     })
 }
 
-struct TagDrops {
-    unread_drops: Vec<firehose::Drop>,
-    read_drops: Vec<firehose::Drop>,
-    saved_drops: Vec<firehose::Drop>,
+const DROPS_PER_PAGE: i64 = 32;
+
+struct TagSection {
+    drops: Vec<firehose::Drop>,
+    pagination: Pagination,
 }
 
-async fn load_tag_drops(
+async fn load_tag_section(
     conn: &mut PgConnection,
     user: &User,
     tag: Tag,
-    unread_limit: i64,
-) -> anyhow::Result<TagDrops> {
-    let unread_drops = firehose::list_drops(
+    status: DropStatus,
+    cursor: Option<firehose::PageCursor>,
+    size: i64,
+) -> anyhow::Result<TagSection> {
+    let page = firehose::list_drops_page(
         &mut *conn,
         user,
         firehose::DropFilters {
             tags: Some(vec![tag.clone()]),
-            status: Some(firehose::DropStatus::Unread),
-        },
-        Some(unread_limit),
-    )
-    .await?;
-
-    let read_drops = firehose::list_drops(
-        &mut *conn,
-        user,
-        firehose::DropFilters {
-            tags: Some(vec![tag.clone()]),
-            status: Some(firehose::DropStatus::Read),
+            status: Some(status),
+            ..Default::default()
         },
-        None,
+        cursor,
+        size,
     )
     .await?;
 
-    let saved_drops = firehose::list_drops(
-        &mut *conn,
-        user,
-        firehose::DropFilters {
-            tags: Some(vec![tag.clone()]),
-            status: Some(firehose::DropStatus::Saved),
-        },
-        None,
-    )
-    .await?;
+    let status = status.to_string();
+    let pagination = Pagination::new(&Member::path(&tag.id), &[("status", &status)], &page);
 
-    Ok(TagDrops {
-        unread_drops,
-        read_drops,
-        saved_drops,
+    Ok(TagSection {
+        drops: page.drops,
+        pagination,
     })
 }
 
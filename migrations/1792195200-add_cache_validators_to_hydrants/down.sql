@@ -0,0 +1,2 @@
+alter table hydrants drop column last_modified;
+alter table hydrants drop column etag;
@@ -0,0 +1,2 @@
+alter table hydrants add column etag text;
+alter table hydrants add column last_modified text;
@@ -0,0 +1,8 @@
+create table hydrant_items (
+    hydrant_id uuid references hydrants(id) on delete cascade not null,
+    key text not null check (key != ''),
+
+    created_at timestamp not null default now(),
+
+    primary key (hydrant_id, key)
+);
@@ -0,0 +1,4 @@
+alter table hydrants drop column next_attempt_at;
+alter table hydrants drop column last_error;
+alter table hydrants drop column failure_count;
+alter table hydrants drop column fetch_interval_minutes;
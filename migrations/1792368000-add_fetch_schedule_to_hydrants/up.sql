@@ -0,0 +1,4 @@
+alter table hydrants add column fetch_interval_minutes integer not null default 60 check (fetch_interval_minutes > 0);
+alter table hydrants add column failure_count integer not null default 0;
+alter table hydrants add column last_error text;
+alter table hydrants add column next_attempt_at timestamp;
@@ -0,0 +1,15 @@
+create table hydrant_fetches (
+    id uuid primary key default gen_random_uuid(),
+    hydrant_id uuid references hydrants(id) on delete cascade not null,
+
+    fetched_at timestamp not null,
+    status integer,
+    bytes bigint,
+    item_count integer,
+    drop_count integer,
+    error text,
+
+    created_at timestamp not null default now()
+);
+
+create index hydrant_fetches_hydrant_id_fetched_at on hydrant_fetches (hydrant_id, fetched_at desc);
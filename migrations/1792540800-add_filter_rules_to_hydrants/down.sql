@@ -0,0 +1,4 @@
+alter table hydrant_fetches drop column filtered_count;
+
+alter table hydrants drop column exclude_rules;
+alter table hydrants drop column include_rules;
@@ -0,0 +1,4 @@
+alter table hydrants add column include_rules text[] not null default '{}';
+alter table hydrants add column exclude_rules text[] not null default '{}';
+
+alter table hydrant_fetches add column filtered_count integer;
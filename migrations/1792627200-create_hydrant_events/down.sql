@@ -0,0 +1,2 @@
+drop table if exists hydrant_events;
+drop type if exists hydrant_event_kind;
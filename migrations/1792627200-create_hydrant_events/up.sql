@@ -0,0 +1,15 @@
+create type hydrant_event_kind as enum ('moved', 'discovered', 'gone', 'failing');
+
+create table hydrant_events (
+    id uuid primary key default gen_random_uuid(),
+    hydrant_id uuid references hydrants(id) on delete cascade not null,
+
+    kind hydrant_event_kind not null,
+    old_url text,
+    new_url text,
+    occurred_at timestamp not null,
+
+    created_at timestamp not null default now()
+);
+
+create index hydrant_events_hydrant_id_occurred_at on hydrant_events (hydrant_id, occurred_at desc);
@@ -0,0 +1,15 @@
+create table hydrant_subscriptions (
+    id uuid primary key default gen_random_uuid(),
+    hydrant_id uuid references hydrants(id) on delete cascade not null unique,
+
+    hub_url text not null,
+    topic_url text not null,
+    secret text not null,
+    requested_at timestamp,
+    lease_expires_at timestamp,
+
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+select manage_updated_at('hydrant_subscriptions');
@@ -0,0 +1,3 @@
+alter table hydrants drop column source_kind;
+
+drop type if exists hydrant_source_kind;
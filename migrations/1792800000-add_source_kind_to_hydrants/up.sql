@@ -0,0 +1,3 @@
+create type hydrant_source_kind as enum ('feed', 'sitemap');
+
+alter table hydrants add column source_kind hydrant_source_kind not null default 'feed';
@@ -0,0 +1,3 @@
+alter table hydrants drop column backfill_read;
+alter table hydrants drop column max_drops_per_fetch;
+alter table hydrants drop column first_fetch_limit;
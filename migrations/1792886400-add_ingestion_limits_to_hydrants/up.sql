@@ -0,0 +1,3 @@
+alter table hydrants add column first_fetch_limit integer check (first_fetch_limit > 0);
+alter table hydrants add column max_drops_per_fetch integer check (max_drops_per_fetch > 0);
+alter table hydrants add column backfill_read boolean not null default false;
@@ -0,0 +1,2 @@
+drop index drops_search;
+alter table drops drop column search;
@@ -0,0 +1,10 @@
+-- Titles, the site a drop is on (without any "www."), and the rest of the URL split into words.
+-- Tags can't go in a generated column, so they're matched separately.
+alter table drops add column search tsvector generated always as (
+    to_tsvector('simple', coalesce(title, ''))
+    || to_tsvector('simple', coalesce(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), ''))
+    || to_tsvector('simple', coalesce(translate(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), '.-', '  '), ''))
+    || to_tsvector('simple', translate(url, '/.?=&-_:#+%~', '            '))
+) stored;
+
+create index drops_search on drops using gin (search);
@@ -0,0 +1,2 @@
+drop index drops_user_id_canonical_url;
+alter table drops drop column canonical_url;
@@ -0,0 +1,5 @@
+alter table drops add column canonical_url text;
+
+-- Drops from before now are left without one. Canonicalizing them takes more than SQL can do, so
+-- the CanonicalizeDrops job fills them in, merging any that turn out to be for the same page.
+create unique index drops_user_id_canonical_url on drops (user_id, canonical_url);
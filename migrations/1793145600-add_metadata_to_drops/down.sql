@@ -0,0 +1,5 @@
+alter table drops drop column metadata_fetched_at;
+alter table drops drop column canonical_link;
+alter table drops drop column image_url;
+alter table drops drop column site_name;
+alter table drops drop column description;
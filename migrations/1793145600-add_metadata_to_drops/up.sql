@@ -0,0 +1,9 @@
+alter table drops add column description text check (description != '');
+alter table drops add column site_name text check (site_name != '');
+alter table drops add column image_url text check (image_url != '');
+
+-- Where the page says it really lives (with <link rel="canonical">). This is only for showing, and
+-- isn't what duplicates are matched on.
+alter table drops add column canonical_link text check (canonical_link != '');
+
+alter table drops add column metadata_fetched_at timestamp;
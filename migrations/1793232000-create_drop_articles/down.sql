@@ -0,0 +1 @@
+drop table if exists drop_articles;
@@ -0,0 +1,12 @@
+-- The main text of a drop's page, already sanitized, so it can be read without the original.
+create table drop_articles (
+    drop_id uuid references drops(id) on delete cascade primary key,
+
+    html text not null,
+    word_count integer not null check (word_count >= 0),
+
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+select manage_updated_at('drop_articles');
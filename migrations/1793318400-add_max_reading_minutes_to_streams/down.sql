@@ -0,0 +1 @@
+alter table streams drop column max_reading_minutes;
@@ -0,0 +1,2 @@
+-- Streams can be limited to quick reads: drops whose articles take at most this long to read.
+alter table streams add column max_reading_minutes integer check (max_reading_minutes > 0);
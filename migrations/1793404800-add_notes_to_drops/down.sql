@@ -0,0 +1,14 @@
+drop index drops_search;
+alter table drops drop column search;
+
+alter table drops add column search tsvector generated always as (
+    to_tsvector('simple', coalesce(title, ''))
+    || to_tsvector('simple', coalesce(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), ''))
+    || to_tsvector('simple', coalesce(translate(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), '.-', '  '), ''))
+    || to_tsvector('simple', translate(url, '/.?=&-_:#+%~', '            '))
+) stored;
+
+create index drops_search on drops using gin (search);
+
+alter table drops drop column highlights;
+alter table drops drop column notes;
@@ -0,0 +1,18 @@
+-- Why a drop was saved, in Markdown, and passages quoted from it. Both are only for the drop's
+-- owner.
+alter table drops add column notes text check (notes != '');
+alter table drops add column highlights text[] not null default '{}';
+
+-- Notes are searchable too. Generated columns can't be changed in place, so this one gets rebuilt.
+drop index drops_search;
+alter table drops drop column search;
+
+alter table drops add column search tsvector generated always as (
+    to_tsvector('simple', coalesce(title, ''))
+    || to_tsvector('simple', coalesce(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), ''))
+    || to_tsvector('simple', coalesce(translate(lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)')), '.-', '  '), ''))
+    || to_tsvector('simple', translate(url, '/.?=&-_:#+%~', '            '))
+    || to_tsvector('simple', coalesce(notes, ''))
+) stored;
+
+create index drops_search on drops using gin (search);
@@ -0,0 +1 @@
+alter table hydrants drop column deactivated_for;
@@ -0,0 +1,16 @@
+-- Why a hydrant turned itself off, so that it can say so instead of looking paused. Hydrants that
+-- their owners turned off leave this empty.
+alter table hydrants add column deactivated_for hydrant_event_kind
+    check (deactivated_for in ('gone', 'failing'));
+
+-- Hydrants that already turned themselves off did it with their latest event.
+update hydrants
+set deactivated_for = latest.kind
+from (
+    select distinct on (hydrant_id) hydrant_id, kind
+    from hydrant_events
+    order by hydrant_id, occurred_at desc
+) latest
+where latest.hydrant_id = hydrants.id
+and not hydrants.active
+and latest.kind in ('gone', 'failing');